@@ -3,9 +3,96 @@
 // For example, one can use the function 'build_tri_up_array(&array)' to debug
 // the block generation because it provides a clean output that makes it easy
 // to verify matrices.
+//
+// The diagonalization routines either compute the eigenvalues only
+// ('lapack_diagonalization') or the eigenvalues along with the requested
+// eigenvectors ('lapack_eigenpairs'). Both are generic over the Scalar trait
+// which dispatches to LAPACK 'sspevd' (f32) or 'dspevd' (f64). The complex
+// hermitian blocks of twisted boundary conditions are diagonalized in double
+// precision with LAPACK 'zhpevd' ('lapack_hermitian_diagonalization').
 
-use lapack::sspevd;
+use lapack::{c64, dspevd, sspevd, zhpevd};
 use ndarray::Array2;
+use num_traits::Float;
+use std::fmt::{Debug, Display};
+
+/// Floating point types in which the blocks of the hamiltonian can be built
+/// and diagonalized.
+pub trait Scalar: Float + Debug + Display + Send + Sync + 'static {
+    /// Calls the LAPACK packed symmetric eigensolver of the right precision.
+    ///
+    /// # Safety
+    ///
+    /// Same requirements as LAPACK '?spevd' on the arrays dimensions.
+    #[allow(clippy::too_many_arguments)]
+    unsafe fn spevd(
+        jobz: u8,
+        uplo: u8,
+        n: i32,
+        ap: &mut [Self],
+        w: &mut [Self],
+        z: &mut [Self],
+        ldz: i32,
+        work: &mut [Self],
+        lwork: i32,
+        iwork: &mut [i32],
+        liwork: i32,
+        info: &mut i32,
+    );
+}
+
+impl Scalar for f32 {
+    unsafe fn spevd(
+        jobz: u8,
+        uplo: u8,
+        n: i32,
+        ap: &mut [f32],
+        w: &mut [f32],
+        z: &mut [f32],
+        ldz: i32,
+        work: &mut [f32],
+        lwork: i32,
+        iwork: &mut [i32],
+        liwork: i32,
+        info: &mut i32,
+    ) {
+        sspevd(
+            jobz, uplo, n, ap, w, z, ldz, work, lwork, iwork, liwork, info,
+        )
+    }
+}
+
+impl Scalar for f64 {
+    unsafe fn spevd(
+        jobz: u8,
+        uplo: u8,
+        n: i32,
+        ap: &mut [f64],
+        w: &mut [f64],
+        z: &mut [f64],
+        ldz: i32,
+        work: &mut [f64],
+        lwork: i32,
+        iwork: &mut [i32],
+        liwork: i32,
+        info: &mut i32,
+    ) {
+        dspevd(
+            jobz, uplo, n, ap, w, z, ldz, work, lwork, iwork, liwork, info,
+        )
+    }
+}
+
+/// Eigenvectors requested when diagonalizing a block of the hamiltonian.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Eigenvectors {
+    /// Eigenvalues only (LAPACK jobz='N')
+    Skip,
+    /// Eigenvectors of the 'k' lowest eigenvalues
+    Lowest(usize),
+    /// All the eigenvectors
+    All,
+}
 
 /// Computes the dimension (N) of an upper triangle matrix using the 1d array length
 /// of it's upper triangle elements. The equation for the length (l) is given by
@@ -19,7 +106,7 @@ use ndarray::Array2;
 /// println!("Matrix has dimension NxN, where N={}", get_matrix_dimension(ap_array_length));
 /// ```
 pub fn get_matrix_dimension(lapack_ap_length: usize) -> usize {
-    let dimension: usize = (((1. + 8. * lapack_ap_length as f32).sqrt() - 1.) / 2.) as usize;
+    let dimension: usize = (((1. + 8. * lapack_ap_length as f64).sqrt() - 1.) / 2.) as usize;
     dimension
 }
 
@@ -33,12 +120,12 @@ pub fn get_matrix_dimension(lapack_ap_length: usize) -> usize {
 /// println!("Matrix A is defined in 2d by the array:\n{:?}", build_tri_up_array(&ap_array));
 /// ```
 #[allow(dead_code)]
-pub fn build_tri_up_array(matrix_elements: &Vec<f32>) -> Array2<f32> {
+pub fn build_tri_up_array<T: Scalar>(matrix_elements: &[T]) -> Array2<T> {
     // Verifying triangular number for matrix dimensions
     let matrix_dim: usize = get_matrix_dimension(matrix_elements.len());
 
     // Initializing upper triangle matrix by setting the diagonal
-    let mut array: Array2<f32> = Array2::zeros((matrix_dim, matrix_dim));
+    let mut array: Array2<T> = Array2::zeros((matrix_dim, matrix_dim));
 
     // Loop over upper triangle indices
     for i in 1..matrix_dim + 1 {
@@ -51,7 +138,7 @@ pub fn build_tri_up_array(matrix_elements: &Vec<f32>) -> Array2<f32> {
 }
 
 /// Diagonalization of upper triangular hermitian matrix using LAPACK 'sspevd'
-/// Fortran implementation.
+/// (or 'dspevd' in double precision) Fortran implementation.
 ///
 /// Examples
 ///
@@ -60,24 +147,72 @@ pub fn build_tri_up_array(matrix_elements: &Vec<f32>) -> Array2<f32> {
 /// let (exit_code, eig_vals): (i32, Vec<f32>) = lapack_diagonalization(elements);
 /// println!("Exit code: {} and eigenvalues = {:?}", exit_code, eig_vals);
 /// ```
-pub fn lapack_diagonalization(lapack_ap_array: Vec<f32>) -> (i32, Vec<f32>) {
+pub fn lapack_diagonalization<T: Scalar>(lapack_ap_array: Vec<T>) -> (i32, Vec<T>) {
+    // Matrix properties
+    let array_order: i32 = get_matrix_dimension(lapack_ap_array.len()) as i32;
+    let mut elements: Vec<T> = lapack_ap_array;
+    let mut eigen_vals: Vec<T> = vec![T::zero(); array_order as usize];
+    let mut eigen_vects: Vec<T> = vec![T::zero(); 1];
+
+    // Working array memory (LAPACK needs at least one element, even for the
+    // empty blocks)
+    let lwork: i32 = (2 * array_order).max(1);
+    let liwork: i32 = 1;
+    let mut work: Vec<T> = vec![T::zero(); lwork as usize];
+    let mut iwork: Vec<i32> = vec![0; liwork as usize];
+
+    // Informative quantities
+    let mut info: i32 = 0;
+
+    unsafe {
+        T::spevd(
+            b'N',
+            b'U',
+            array_order,
+            &mut elements,
+            &mut eigen_vals,
+            &mut eigen_vects,
+            1,
+            &mut work,
+            lwork,
+            &mut iwork,
+            liwork,
+            &mut info,
+        )
+    }
+    (info, eigen_vals)
+}
+
+/// Diagonalization of upper triangular complex hermitian matrix using LAPACK
+/// 'zhpevd' Fortran implementation (the elements are packed column-wise like
+/// the real blocks).
+///
+/// Examples
+///
+/// ```rust
+/// let elements: Vec<c64> = vec![c64::new(0., 0.), c64::new(0., 1.), c64::new(0., 0.)];
+/// let (exit_code, eig_vals): (i32, Vec<f64>) = lapack_hermitian_diagonalization(elements);
+/// ```
+pub fn lapack_hermitian_diagonalization(lapack_ap_array: Vec<c64>) -> (i32, Vec<f64>) {
     // Matrix properties
-    let mut elements: Vec<f32> = lapack_ap_array.clone();
     let array_order: i32 = get_matrix_dimension(lapack_ap_array.len()) as i32;
-    let mut eigen_vals: Vec<f32> = vec![0.0; array_order as usize];
-    let mut eigen_vects: Vec<f32> = Vec::with_capacity(array_order as usize);
+    let mut elements: Vec<c64> = lapack_ap_array;
+    let mut eigen_vals: Vec<f64> = vec![0.; array_order as usize];
+    let mut eigen_vects: Vec<c64> = vec![c64::new(0., 0.); 1];
 
     // Working array memory
-    let lwork: i32 = 2 * array_order as i32;
+    let lwork: i32 = array_order.max(1);
+    let lrwork: i32 = array_order.max(1);
     let liwork: i32 = 1;
-    let mut work: Vec<f32> = Vec::with_capacity(lwork as usize);
-    let mut iwork: Vec<i32> = Vec::with_capacity(liwork as usize);
+    let mut work: Vec<c64> = vec![c64::new(0., 0.); lwork as usize];
+    let mut rwork: Vec<f64> = vec![0.; lrwork as usize];
+    let mut iwork: Vec<i32> = vec![0; liwork as usize];
 
     // Informative quantities
     let mut info: i32 = 0;
 
     unsafe {
-        sspevd(
+        zhpevd(
             b'N',
             b'U',
             array_order,
@@ -87,6 +222,8 @@ pub fn lapack_diagonalization(lapack_ap_array: Vec<f32>) -> (i32, Vec<f32>) {
             1,
             &mut work,
             lwork,
+            &mut rwork,
+            lrwork,
             &mut iwork,
             liwork,
             &mut info,
@@ -95,12 +232,85 @@ pub fn lapack_diagonalization(lapack_ap_array: Vec<f32>) -> (i32, Vec<f32>) {
     (info, eigen_vals)
 }
 
+/// Diagonalization of upper triangular hermitian matrix using LAPACK 'sspevd'
+/// (or 'dspevd' in double precision) Fortran implementation, keeping the
+/// requested eigenvectors.
+///
+/// It outputs the exit code, all the eigenvalues (increasing order) and the
+/// eigenvectors of the lowest eigenvalues (one vector per eigenvalue).
+///
+/// Examples
+///
+/// ```rust
+/// let elements: Vec<f32> = vec![0., 1., 0.];
+/// let (exit_code, eig_vals, eig_vects) = lapack_eigenpairs(elements, Eigenvectors::Lowest(1));
+/// println!("Ground state {:?} has energy {}", eig_vects[0], eig_vals[0]);
+/// ```
+pub fn lapack_eigenpairs<T: Scalar>(
+    lapack_ap_array: Vec<T>,
+    vectors: Eigenvectors,
+) -> (i32, Vec<T>, Vec<Vec<T>>) {
+    // Number of eigenvectors to keep
+    let array_order: usize = get_matrix_dimension(lapack_ap_array.len());
+    let n_vectors: usize = match vectors {
+        Eigenvectors::Skip => {
+            let (info, eigen_vals) = lapack_diagonalization(lapack_ap_array);
+            return (info, eigen_vals, Vec::new());
+        }
+        Eigenvectors::Lowest(k) => k.min(array_order),
+        Eigenvectors::All => array_order,
+    };
+
+    // Matrix properties
+    let mut elements: Vec<T> = lapack_ap_array;
+    let mut eigen_vals: Vec<T> = vec![T::zero(); array_order];
+    let mut eigen_vects: Vec<T> = vec![T::zero(); (array_order * array_order).max(1)];
+
+    // Working array memory (jobz='V')
+    let lwork: i32 = (1 + 6 * array_order + array_order * array_order) as i32;
+    let liwork: i32 = (3 + 5 * array_order) as i32;
+    let mut work: Vec<T> = vec![T::zero(); lwork as usize];
+    let mut iwork: Vec<i32> = vec![0; liwork as usize];
+
+    // Informative quantities
+    let mut info: i32 = 0;
+
+    unsafe {
+        T::spevd(
+            b'V',
+            b'U',
+            array_order as i32,
+            &mut elements,
+            &mut eigen_vals,
+            &mut eigen_vects,
+            array_order.max(1) as i32,
+            &mut work,
+            lwork,
+            &mut iwork,
+            liwork,
+            &mut info,
+        )
+    }
+
+    // Eigenvectors are stored column-wise
+    let vects: Vec<Vec<T>> = eigen_vects
+        .chunks(array_order.max(1))
+        .take(n_vectors)
+        .map(|column| column.to_vec())
+        .collect();
+    (info, eigen_vals, vects)
+}
+
 #[cfg(test)]
 mod tests {
+    use lapack::c64;
     use ndarray::{arr2, Array2};
     use std::assert_eq;
 
-    use crate::array_utils::{build_tri_up_array, get_matrix_dimension, lapack_diagonalization};
+    use crate::array_utils::{
+        build_tri_up_array, get_matrix_dimension, lapack_diagonalization, lapack_eigenpairs,
+        lapack_hermitian_diagonalization, Eigenvectors,
+    };
 
     #[test]
     fn check_matrix_dimension() {
@@ -127,4 +337,50 @@ mod tests {
         let output: (i32, Vec<f32>) = (0, vec![1., 1., 1.]);
         assert_eq!(output, lapack_diagonalization(elements))
     }
+
+    #[test]
+    fn check_lapack_empty() {
+        // Empty blocks have no eigenvalues (and no eigenvectors)
+        assert_eq!((0, vec![]), lapack_diagonalization::<f64>(vec![]));
+        let (info, eig_vals, eig_vects) = lapack_eigenpairs::<f64>(vec![], Eigenvectors::All);
+        assert_eq!(0, info);
+        assert!(eig_vals.is_empty() && eig_vects.is_empty());
+    }
+
+    #[test]
+    fn check_lapack_dspevd() {
+        // Double precision resolves splittings beyond f32 precision
+        let elements: Vec<f64> = vec![1., 1e-5, 1.];
+        let (info, eig_vals): (i32, Vec<f64>) = lapack_diagonalization(elements);
+        assert_eq!(0, info);
+        assert!((eig_vals[1] - eig_vals[0] - 2e-5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn check_lapack_zhpevd() {
+        // Matrix [[1, i], [-i, 1]] has eigenvalues 0 and 2
+        let elements: Vec<c64> = vec![c64::new(1., 0.), c64::new(0., 1.), c64::new(1., 0.)];
+        let (info, eig_vals): (i32, Vec<f64>) = lapack_hermitian_diagonalization(elements);
+        assert_eq!(0, info);
+        assert!(eig_vals[0].abs() < 1e-12 && (eig_vals[1] - 2.).abs() < 1e-12);
+    }
+
+    #[test]
+    fn check_lapack_eigenpairs() {
+        // Matrix [[0, 1], [1, 0]] has eigenvectors (1, -1) and (1, 1)
+        let elements: Vec<f32> = vec![0., 1., 0.];
+        let (info, eig_vals, eig_vects) = lapack_eigenpairs(elements.clone(), Eigenvectors::All);
+        assert_eq!((0, vec![-1., 1.]), (info, eig_vals));
+        assert_eq!(2, eig_vects.len());
+        let norm: f32 = 1. / 2_f32.sqrt();
+        assert!((eig_vects[0][0] + eig_vects[0][1]).abs() < 1e-6);
+        assert!((eig_vects[1][0] - eig_vects[1][1]).abs() < 1e-6);
+        assert!((eig_vects[1][0].abs() - norm).abs() < 1e-6);
+
+        // Only the lowest eigenvector is kept
+        let (_, _, lowest) = lapack_eigenpairs(elements.clone(), Eigenvectors::Lowest(1));
+        assert_eq!(1, lowest.len());
+        let (_, _, skipped) = lapack_eigenpairs(elements, Eigenvectors::Skip);
+        assert!(skipped.is_empty());
+    }
 }
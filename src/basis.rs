@@ -0,0 +1,147 @@
+// This module defines the tools used to look up Fock states while building the
+// blocks of the hamiltonian.
+//
+// Fock states are stored as unsigned bitstrings (State) of 2 * n_sites bits,
+// which allows clusters of up to 32 sites (64 with the 'u128' feature). The
+// fermionic signs are never stored inside the states: they are carried by the
+// matrix elements instead.
+//
+// StateBitSet objects keep track of the states already visited in the whole
+// Fock space using one bit per state, so that membership is verified in
+// constant time instead of scanning a list of visited states.
+//
+// BlockBasis objects store the states of one block of the hamiltonian along
+// with a hash map from the state integer to its row index inside the block.
+
+use std::collections::HashMap;
+
+/// Bitstring representation of a Fock state.
+#[cfg(not(feature = "u128"))]
+pub type State = u64;
+
+/// Bitstring representation of a Fock state.
+#[cfg(feature = "u128")]
+pub type State = u128;
+
+/// Largest number of sites a Fock state can represent.
+pub const MAX_SITES: u32 = State::BITS / 2;
+
+#[derive(Debug)]
+pub struct StateBitSet {
+    // Private attributes
+    words: Vec<u64>,
+}
+
+impl StateBitSet {
+    /// Initializes an empty bitset able to store states 0..n_states.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// // Whole Fock space of a 6 sites cluster
+    /// let visited = StateBitSet::new(4_usize.pow(6));
+    /// ```
+    pub fn new(n_states: usize) -> StateBitSet {
+        StateBitSet {
+            words: vec![0; n_states.div_ceil(64)],
+        }
+    }
+
+    /// Marks given state as visited.
+    pub fn insert(&mut self, state: State) {
+        let state: usize = state as usize;
+        self.words[state / 64] |= 1 << (state % 64);
+    }
+
+    /// Verifies if given state was already visited.
+    pub fn contains(&self, state: State) -> bool {
+        let state: usize = state as usize;
+        self.words[state / 64] & (1 << (state % 64)) != 0
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct BlockBasis {
+    // Public attributes
+    pub states: Vec<State>,
+
+    // Private attributes
+    index: HashMap<State, usize>,
+}
+
+impl BlockBasis {
+    /// Adds given state at the end of the basis if it is not already inside.
+    /// Returns true if the state was added.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let mut basis = BlockBasis::default();
+    /// basis.push(9);
+    /// assert_eq!(false, basis.push(9));
+    /// ```
+    pub fn push(&mut self, state: State) -> bool {
+        if self.index.contains_key(&state) {
+            return false;
+        }
+        self.index.insert(state, self.states.len());
+        self.states.push(state);
+        true
+    }
+
+    /// Outputs the row index of given state inside the block.
+    pub fn index_of(&self, state: State) -> Option<usize> {
+        self.index.get(&state).copied()
+    }
+
+    /// Number of states inside the block.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Sorts the states in increasing order and updates their row indices.
+    pub fn sort(&mut self) {
+        self.states.sort();
+        for (row, state) in self.states.iter().enumerate() {
+            self.index.insert(*state, row);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use crate::basis::{BlockBasis, StateBitSet};
+
+    #[test]
+    fn test_state_bitset() {
+        let mut visited: StateBitSet = StateBitSet::new(256);
+        visited.insert(0);
+        visited.insert(70);
+        visited.insert(255);
+        assert!(visited.contains(0));
+        assert!(visited.contains(70));
+        assert!(visited.contains(255));
+        assert!(!visited.contains(1));
+        assert!(!visited.contains(128));
+    }
+
+    #[test]
+    fn test_block_basis() {
+        let mut basis: BlockBasis = BlockBasis::default();
+        assert!(basis.push(12));
+        assert!(basis.push(9));
+        assert!(!basis.push(9));
+        assert!(basis.push(10));
+        assert_eq!(Some(1), basis.index_of(9));
+
+        // Sorting must keep indices consistent with states
+        basis.sort();
+        assert_eq!(vec![9, 10, 12], basis.states);
+        assert_eq!(Some(2), basis.index_of(12));
+        assert_eq!(None, basis.index_of(17));
+        assert_eq!(3, basis.len());
+    }
+}
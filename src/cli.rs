@@ -0,0 +1,724 @@
+// This module defines the command-line interface of the programm.
+//
+// The model parameters, the selected sectors, the solver and the output
+// directory are given as flags to one of the subcommands
+//
+//      hubbies spectrum --lattice chain:6 -t 1 -u 2
+//      hubbies ground-state --lattice square:4x4 -u 8 --hoppings -0.3,0.2 --n-particles 14
+//      hubbies sweep --lattice chain:8 -u 4 --v 1 --u-values 2,4,6 --n-up 4 --n-down 4
+//      hubbies ground-state --lattice square:4x4 -u 8 --n-up 8 --n-down 8 --solver lanczos
+//      hubbies sweep --lattice betts:10 --u-values 0,2,4,8 --solver lanczos
+//      hubbies grand-canonical --lattice chain:8 -u 4 --particle-hole --mu-values -2,0,2
+//      hubbies spectrum --lattice chain:6 --boundary antiperiodic
+//      hubbies spectrum --lattice square:2x2 -u 4 --n-particles 4 --total-spin
+//      hubbies spectrum --lattice chain:6 -u 4 --particle-hole-mapping
+//      hubbies symmetry --lattice square:4x4 -u 8 --n-particles 14 --point-group c4v
+//      hubbies spin-inversion --lattice chain:8 -u 4 --n-up 4 --n-down 4 --n-eigen 4
+//      hubbies momentum --lattice chain:10 -u 4 --n-up 5 --n-down 5 --n-eigen 3
+//      hubbies twist --lattice square:2x4 -u 4 --n-particles 8 --twist-values 0,0.5,1,1.5
+//      hubbies disorder --lattice chain:8 -u 4 --energy-disorder 2 --realizations 100 --seed 7
+//      hubbies magnetization --lattice chain:8 -u 4 --n-particles 8 --field-values 0,0.5,1
+//
+// where the lattice is either 'chain:N', 'square:LXxLY', 'betts:N' or the path
+// to a bonds file (see './src/lattice.rs'). The same subcommands can also be
+// described inside a configuration file (see './src/config.rs') given to
+//
+//      hubbies run config.toml
+
+use clap::builder::RangedU64ValueParser;
+use clap::{Args, FromArgMatches, Parser, Subcommand, ValueEnum};
+use serde::{de::Error, Deserialize, Deserializer, Serialize};
+use std::f64::consts::PI;
+
+use crate::array_utils::Eigenvectors;
+use crate::basis::MAX_SITES;
+use crate::disorder::{Disorder, Distribution};
+use crate::lanczos::Lanczos;
+use crate::lattice::{Boundary, Lattice};
+use crate::sectors::Sector;
+use crate::symmetry::PointGroup;
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "hubbies",
+    version,
+    about = "Exact diagonalization of the Hubbard model"
+)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand, Serialize, Deserialize)]
+#[serde(tag = "command", rename_all = "kebab-case")]
+pub enum Command {
+    /// Computes the full spectrum of the selected sectors (dense LAPACK)
+    Spectrum {
+        #[command(flatten)]
+        #[serde(default)]
+        model: ModelArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        sectors: SectorArgs,
+        /// Eigenvectors saved for each block: 'skip', 'all' or the number of
+        /// lowest eigenvectors
+        #[arg(long, default_value = "skip", value_parser = parse_vectors)]
+        #[serde(default = "skip_vectors", with = "vectors_spec")]
+        vectors: Eigenvectors,
+        /// Label every level with its total spin S and collapse the degenerate
+        /// levels into multiplets
+        #[arg(long)]
+        #[serde(default)]
+        total_spin: bool,
+        /// Label every level with its eta-pairing pseudospin (bipartite
+        /// clusters with uniform parameters and V = 0)
+        #[arg(long)]
+        #[serde(default)]
+        pseudospin: bool,
+        /// Obtain the sectors with more than n_sites fermions from their
+        /// particle-hole images instead of diagonalizing them (eigenvalues only)
+        #[arg(long)]
+        #[serde(default)]
+        particle_hole_mapping: bool,
+    },
+    /// Computes the lowest eigenvalues of the selected sectors
+    GroundState {
+        #[command(flatten)]
+        #[serde(default)]
+        model: ModelArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        sectors: SectorArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        solver: SolverArgs,
+    },
+    /// Computes the lowest eigenvalues of the selected sectors for every
+    /// (t, u) point of the lists of hopping and interaction values
+    Sweep {
+        #[command(flatten)]
+        #[serde(default)]
+        model: ModelArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        sectors: SectorArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        solver: SolverArgs,
+        /// Hopping values (comma separated, defaults to '-t')
+        #[arg(long, value_delimiter = ',')]
+        #[serde(default)]
+        t_values: Vec<f64>,
+        /// Interaction values (comma separated)
+        #[arg(long, value_delimiter = ',', required = true)]
+        u_values: Vec<f64>,
+    },
+    /// Computes the grand-canonical ground state (and its filling) for a list
+    /// of chemical potentials, from the lowest eigenvalue of each sector
+    GrandCanonical {
+        #[command(flatten)]
+        #[serde(default)]
+        model: ModelArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        sectors: SectorArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        solver: SolverArgs,
+        /// Chemical potentials (comma separated, replaces '--mu')
+        #[arg(
+            long,
+            value_delimiter = ',',
+            required = true,
+            allow_hyphen_values = true
+        )]
+        mu_values: Vec<f64>,
+    },
+    /// Computes the ground state (and its magnetization) for a list of
+    /// magnetic fields, from the lowest eigenvalue of each sector
+    Magnetization {
+        #[command(flatten)]
+        #[serde(default)]
+        model: ModelArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        sectors: SectorArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        solver: SolverArgs,
+        /// Magnetic fields (comma separated, replaces '--field')
+        #[arg(
+            long,
+            value_delimiter = ',',
+            required = true,
+            allow_hyphen_values = true
+        )]
+        field_values: Vec<f64>,
+    },
+    /// Computes the lowest eigenvalues of the selected sectors for many random
+    /// realizations of the disorder, and their averages
+    Disorder {
+        #[command(flatten)]
+        #[serde(default)]
+        model: ModelArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        sectors: SectorArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        solver: SolverArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        disorder: DisorderArgs,
+    },
+    /// Computes the lowest eigenvalues of the selected sectors on a grid of
+    /// twist angles (complex hermitian blocks), and their twist averages
+    Twist {
+        #[command(flatten)]
+        #[serde(default)]
+        model: ModelArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        sectors: SectorArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        solver: SolverArgs,
+        /// Twist angles in units of pi (comma separated, used along each period)
+        #[arg(
+            long,
+            value_delimiter = ',',
+            required = true,
+            allow_hyphen_values = true
+        )]
+        twist_values: Vec<f64>,
+    },
+    /// Computes the lowest eigenvalues of every momentum block of the selected
+    /// sectors on a translation invariant ring (complex hermitian blocks)
+    Momentum {
+        #[command(flatten)]
+        #[serde(default)]
+        model: ModelArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        sectors: SectorArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        solver: SolverArgs,
+    },
+    /// Computes the lowest eigenvalues of every irreducible representation of a
+    /// point group of the cluster inside the selected sectors
+    Symmetry {
+        #[command(flatten)]
+        #[serde(default)]
+        model: ModelArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        sectors: SectorArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        solver: SolverArgs,
+        /// Point group of the cluster
+        #[arg(long, value_enum, default_value_t = PointGroup::Reflection)]
+        #[serde(default = "reflection_group")]
+        point_group: PointGroup,
+    },
+    /// Computes the lowest eigenvalues of the even (even total spin) and odd
+    /// (odd total spin) states of the selected S_z = 0 sectors
+    SpinInversion {
+        #[command(flatten)]
+        #[serde(default)]
+        model: ModelArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        sectors: SectorArgs,
+        #[command(flatten)]
+        #[serde(default)]
+        solver: SolverArgs,
+    },
+    /// Runs the subcommand described inside a TOML (or JSON) configuration file
+    #[serde(skip)]
+    Run {
+        /// Path to the configuration file
+        config: String,
+    },
+}
+
+#[derive(Debug, Args, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ModelArgs {
+    /// Cluster: 'chain:N', 'square:LXxLY', 'betts:N' or a bonds file
+    #[arg(short, long, default_value = "chain:6")]
+    pub lattice: String,
+    /// Hopping amplitude
+    #[arg(short, default_value_t = 1.)]
+    pub t: f64,
+    /// Hoppings of the next neighbour shells in units of t (t'/t, t''/t, ...)
+    #[arg(long, value_delimiter = ',', allow_hyphen_values = true)]
+    pub hoppings: Vec<f64>,
+    /// On-site interaction
+    #[arg(short, default_value_t = 2.)]
+    pub u: f64,
+    /// Boundary conditions ('open', 'periodic', 'antiperiodic' or 'twisted:THETA'
+    /// with THETA in units of pi), for both periods or one per period
+    #[arg(long, value_delimiter = ',', default_value = "periodic")]
+    pub boundary: Vec<String>,
+    /// Extended (density-density) repulsion along the bonds
+    #[arg(long, default_value_t = 0.)]
+    pub v: f64,
+    /// Chemical potential
+    #[arg(long, default_value_t = 0., allow_hyphen_values = true)]
+    pub mu: f64,
+    /// Uniform magnetic field h coupled to N_up - N_down
+    #[arg(long, default_value_t = 0., allow_hyphen_values = true)]
+    pub field: f64,
+    /// Use the particle-hole symmetric interactions U (n_up - 1/2)(n_down - 1/2)
+    /// and V (n_i - 1)(n_j - 1)
+    #[arg(long)]
+    pub particle_hole: bool,
+    /// Number of threads (0 uses all available cores)
+    #[arg(long, default_value_t = 0)]
+    pub threads: usize,
+    /// Directory of the output data files
+    #[arg(short, long, default_value = "./Data")]
+    pub output: String,
+}
+
+#[derive(Debug, Args, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SectorArgs {
+    /// Only keep the sectors with this number of spin up fermions
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n_up: Option<u32>,
+    /// Only keep the sectors with this number of spin down fermions
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n_down: Option<u32>,
+    /// Only keep the sectors with this total number of fermions
+    #[arg(long)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub n_particles: Option<u32>,
+}
+
+impl ModelArgs {
+    /// Builds the cluster described by the lattice flag, with the longer
+    /// range hoppings of the next neighbour shells.
+    pub fn build_lattice(&self) -> Result<Lattice, String> {
+        let mut lattice: Lattice = parse_lattice(&self.lattice)?;
+        if !self.hoppings.is_empty() && lattice.periods.is_none() {
+            return Err(format!(
+                "Lattice '{}' has no neighbour shells, add the longer range bonds to its file",
+                self.lattice
+            ));
+        }
+        if !self.hoppings.is_empty() {
+            let n_shells: usize = lattice.neighbour_shells().len();
+            for (shell, &hopping) in (2..).zip(self.hoppings.iter()) {
+                if shell > n_shells {
+                    return Err(format!(
+                        "Lattice '{}' has {} neighbour shells",
+                        self.lattice, n_shells
+                    ));
+                }
+                lattice.set_shell_hopping(shell, hopping);
+            }
+        }
+
+        // Boundary conditions along each period
+        let boundaries: Vec<Boundary> = self
+            .boundary
+            .iter()
+            .map(|spec| parse_boundary(spec))
+            .collect::<Result<_, _>>()?;
+        lattice.boundaries = match boundaries.as_slice() {
+            [boundary] => [*boundary; 2],
+            [boundary_1, boundary_2] => [*boundary_1, *boundary_2],
+            _ => return Err(String::from("Expected one or two boundary conditions")),
+        };
+        if lattice.periods.is_none() && boundaries.iter().any(|&b| b != Boundary::Periodic) {
+            return Err(format!("Lattice '{}' has no boundaries", self.lattice));
+        }
+        Ok(lattice)
+    }
+}
+
+impl SectorArgs {
+    /// Lists the sectors of a cluster of 'n_sites' sites matching the filters.
+    pub fn select(&self, n_sites: u32) -> Vec<Sector> {
+        Sector::all(n_sites)
+            .into_iter()
+            .filter(|s| self.n_up.is_none_or(|n| s.n_up == n))
+            .filter(|s| self.n_down.is_none_or(|n| s.n_down == n))
+            .filter(|s| self.n_particles.is_none_or(|n| s.n_up + s.n_down == n))
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Solver {
+    /// Dense diagonalization of the sector block
+    Lapack,
+    /// Matrix-free Lanczos solver
+    Lanczos,
+}
+
+#[derive(Debug, Args, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SolverArgs {
+    /// Eigensolver used for each sector
+    #[arg(long, value_enum, default_value_t = Solver::Lapack)]
+    pub solver: Solver,
+    /// Number of lowest eigenvalues kept per sector
+    #[arg(long, default_value_t = 1, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    #[serde(deserialize_with = "at_least_one")]
+    pub n_eigen: usize,
+    /// Maximum number of Lanczos iterations
+    #[arg(long, default_value_t = 300, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    #[serde(deserialize_with = "at_least_one")]
+    pub max_iter: usize,
+    /// Convergence tolerance on the Lanczos residuals (relative to the largest
+    /// Ritz value)
+    #[arg(long, default_value_t = 1e-10)]
+    pub tolerance: f64,
+    /// Fully reorthogonalize the Lanczos vectors (always done when more than
+    /// one eigenvalue is kept, to avoid spurious copies of converged levels)
+    #[arg(long)]
+    pub reorthogonalize: bool,
+}
+
+#[derive(Debug, Args, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DisorderArgs {
+    /// Distribution of the random fluctuations
+    #[arg(long, value_enum, default_value_t = Distribution::Box)]
+    pub distribution: Distribution,
+    /// Width of the on-site energies fluctuations
+    #[arg(long, default_value_t = 0.)]
+    pub energy_disorder: f64,
+    /// Relative width of the on-site interactions fluctuations
+    #[arg(long, default_value_t = 0.)]
+    pub interaction_disorder: f64,
+    /// Relative width of the hoppings fluctuations
+    #[arg(long, default_value_t = 0.)]
+    pub hopping_disorder: f64,
+    /// Number of realizations of the disorder
+    #[arg(long, default_value_t = 100, value_parser = RangedU64ValueParser::<usize>::new().range(1..))]
+    #[serde(deserialize_with = "at_least_one")]
+    pub realizations: usize,
+    /// Seed of the first realization (realization r uses seed + r, wrapping
+    /// around u64::MAX)
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+}
+
+impl DisorderArgs {
+    /// Disorder strengths given by the flags.
+    pub fn disorder(&self) -> Disorder {
+        Disorder {
+            distribution: self.distribution,
+            energies: self.energy_disorder,
+            interactions: self.interaction_disorder,
+            hoppings: self.hopping_disorder,
+        }
+    }
+}
+
+impl SolverArgs {
+    /// Lanczos parameters given by the flags, the Lanczos vectors being
+    /// reorthogonalized as soon as excited levels are requested.
+    pub fn lanczos(&self) -> Lanczos {
+        Lanczos {
+            n_eigen: self.n_eigen,
+            max_iter: self.max_iter,
+            tolerance: self.tolerance,
+            reorthogonalize: self.reorthogonalize || self.n_eigen > 1,
+        }
+    }
+}
+
+/// Builds flags arguments from their default values (used for the missing
+/// entries of configuration files).
+fn default_args<A: Args + FromArgMatches>() -> A {
+    let command = A::augment_args(clap::Command::new("defaults"));
+    A::from_arg_matches(&command.get_matches_from(["defaults"])).unwrap()
+}
+
+impl Default for ModelArgs {
+    fn default() -> Self {
+        default_args()
+    }
+}
+
+impl Default for SectorArgs {
+    fn default() -> Self {
+        default_args()
+    }
+}
+
+impl Default for SolverArgs {
+    fn default() -> Self {
+        default_args()
+    }
+}
+
+impl Default for DisorderArgs {
+    fn default() -> Self {
+        default_args()
+    }
+}
+
+/// Default point group of the symmetry subcommand.
+fn reflection_group() -> PointGroup {
+    PointGroup::Reflection
+}
+
+/// Default eigenvectors of the spectrum subcommand.
+fn skip_vectors() -> Eigenvectors {
+    Eigenvectors::Skip
+}
+
+/// Reads a number of eigenvalues (or of Lanczos iterations, realizations)
+/// inside configuration files, which must be at least 1 like the corresponding
+/// flags.
+fn at_least_one<'de, D: Deserializer<'de>>(de: D) -> Result<usize, D::Error> {
+    match usize::deserialize(de)? {
+        0 => Err(D::Error::custom("expected at least 1, found 0")),
+        count => Ok(count),
+    }
+}
+
+/// Writes (and reads) the eigenvectors flag as a string inside configuration
+/// files ('skip', 'all' or a number of eigenvectors).
+mod vectors_spec {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+    use crate::array_utils::Eigenvectors;
+    use crate::cli::parse_vectors;
+
+    pub fn serialize<S: Serializer>(vectors: &Eigenvectors, ser: S) -> Result<S::Ok, S::Error> {
+        match vectors {
+            Eigenvectors::Skip => ser.serialize_str("skip"),
+            Eigenvectors::All => ser.serialize_str("all"),
+            Eigenvectors::Lowest(k) => ser.serialize_str(&k.to_string()),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(de: D) -> Result<Eigenvectors, D::Error> {
+        let spec: String = String::deserialize(de)?;
+        parse_vectors(&spec).map_err(D::Error::custom)
+    }
+}
+
+/// Parses the lattice flag ('chain:N', 'square:LXxLY', 'betts:N' or the path
+/// to a bonds file).
+///
+/// Examples
+///
+/// ```rust
+/// let lattice: Lattice = parse_lattice("square:4x2").unwrap();
+/// ```
+pub fn parse_lattice(spec: &str) -> Result<Lattice, String> {
+    let size = |n: &str| n.parse::<u32>().map_err(|e| format!("'{}': {}", n, e));
+
+    // Number of sites checked before building the cluster
+    let check = |n_sites: u32| {
+        if n_sites == 0 || n_sites > MAX_SITES {
+            Err(format!(
+                "Clusters have 1 to {} sites, found {}",
+                MAX_SITES, n_sites
+            ))
+        } else {
+            Ok(n_sites)
+        }
+    };
+    match spec.split_once(':') {
+        Some(("chain", n)) => Ok(Lattice::chain(check(size(n)?)?)),
+        Some(("betts", n)) => {
+            let n_sites: u32 = check(size(n)?)?;
+            match Lattice::tilted_period(n_sites) {
+                Some(_) => Ok(Lattice::betts(n_sites)),
+                None => Err(format!("No tilted square cluster has {} sites", n_sites)),
+            }
+        }
+        Some(("square", dims)) => match dims.split_once('x') {
+            Some((lx, ly)) => {
+                let (lx, ly): (u32, u32) = (size(lx)?, size(ly)?);
+                check(lx.saturating_mul(ly))?;
+                Ok(Lattice::square(lx, ly))
+            }
+            None => Err(format!("Square cluster '{}' should be LXxLY", dims)),
+        },
+        _ => {
+            let lattice: Lattice =
+                Lattice::from_file(spec).map_err(|e| format!("Lattice file '{}': {}", spec, e))?;
+            check(lattice.n_sites)?;
+            Ok(lattice)
+        }
+    }
+}
+
+/// Parses a boundary condition ('open', 'periodic', 'antiperiodic' or
+/// 'twisted:THETA' with THETA in units of pi).
+pub fn parse_boundary(spec: &str) -> Result<Boundary, String> {
+    match spec.split_once(':') {
+        Some(("twisted", theta)) => theta
+            .parse::<f64>()
+            .map(|theta| Boundary::Twisted(theta * PI))
+            .map_err(|e| format!("'{}': {}", theta, e)),
+        _ => match spec {
+            "open" => Ok(Boundary::Open),
+            "periodic" => Ok(Boundary::Periodic),
+            "antiperiodic" => Ok(Boundary::Antiperiodic),
+            _ => Err(format!(
+                "Expected 'open', 'periodic', 'antiperiodic' or 'twisted:THETA', found '{}'",
+                spec
+            )),
+        },
+    }
+}
+
+/// Parses the eigenvectors flag ('skip', 'all' or a number of eigenvectors).
+pub fn parse_vectors(spec: &str) -> Result<Eigenvectors, String> {
+    match spec {
+        "skip" => Ok(Eigenvectors::Skip),
+        "all" => Ok(Eigenvectors::All),
+        _ => spec
+            .parse::<usize>()
+            .map(Eigenvectors::Lowest)
+            .map_err(|_| format!("Expected 'skip', 'all' or a number, found '{}'", spec)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use clap::Parser;
+
+    use std::f64::consts::PI;
+
+    use crate::cli::{parse_boundary, parse_lattice, Cli, Command, Solver, SolverArgs};
+    use crate::lattice::Boundary;
+
+    #[test]
+    fn test_parse_lattice() {
+        assert_eq!(6, parse_lattice("chain:6").unwrap().n_sites);
+        assert_eq!(8, parse_lattice("square:4x2").unwrap().n_sites);
+        assert_eq!(10, parse_lattice("betts:10").unwrap().n_sites);
+        assert!(parse_lattice("square:4").is_err());
+        assert!(parse_lattice("./no/such/file.txt").is_err());
+
+        // Invalid clusters are errors instead of panics
+        assert!(parse_lattice("betts:11").is_err());
+        assert!(parse_lattice("chain:0").is_err());
+        assert!(parse_lattice("square:0x4").is_err());
+        assert!(parse_lattice("chain:100").is_err());
+    }
+
+    #[test]
+    fn test_parse_boundary() {
+        assert_eq!(Boundary::Open, parse_boundary("open").unwrap());
+        assert_eq!(
+            Boundary::Twisted(0.5 * PI),
+            parse_boundary("twisted:0.5").unwrap()
+        );
+        assert!(parse_boundary("twisted").is_err());
+        assert!(parse_boundary("closed").is_err());
+    }
+
+    #[test]
+    fn test_cli_hoppings() {
+        let cli: Cli = Cli::parse_from([
+            "hubbies",
+            "spectrum",
+            "--lattice",
+            "square:4x4",
+            "--hoppings",
+            "-0.3,0.2",
+        ]);
+        match cli.command {
+            Command::Spectrum { mut model, .. } => {
+                // Nearest neighbours, diagonals and sites at distance 2 (two
+                // closest images)
+                assert_eq!(32 + 32 + 32, model.build_lattice().unwrap().bonds.len());
+                model.hoppings = vec![0.; 10];
+                assert!(model.build_lattice().is_err());
+            }
+            _ => panic!("Expected the spectrum subcommand"),
+        }
+    }
+
+    #[test]
+    fn test_cli_ground_state() {
+        let cli: Cli = Cli::parse_from([
+            "hubbies",
+            "ground-state",
+            "--lattice",
+            "chain:4",
+            "-u",
+            "4",
+            "--n-particles",
+            "4",
+            "--solver",
+            "lanczos",
+        ]);
+        match cli.command {
+            Command::GroundState {
+                model,
+                sectors,
+                solver,
+            } => {
+                assert_eq!(4, model.build_lattice().unwrap().n_sites);
+                assert_eq!(4., model.u);
+                assert_eq!(Solver::Lanczos, solver.solver);
+                // (0, 4), (1, 3), ..., (4, 0)
+                assert_eq!(5, sectors.select(4).len());
+            }
+            _ => panic!("Expected the ground-state subcommand"),
+        }
+
+        // At least one eigenvalue is kept per sector (and one realization of
+        // the disorder is averaged)
+        assert!(Cli::try_parse_from(["hubbies", "ground-state", "--n-eigen", "0"]).is_err());
+        assert!(Cli::try_parse_from(["hubbies", "disorder", "--realizations", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_cli_lanczos() {
+        // At least one Lanczos iteration, from the flags or the files
+        assert!(Cli::try_parse_from(["hubbies", "ground-state", "--max-iter", "0"]).is_err());
+        assert!(toml::from_str::<SolverArgs>("max_iter = 0").is_err());
+        let solver: SolverArgs = toml::from_str("max_iter = 1").unwrap();
+        assert_eq!(1, solver.max_iter);
+
+        // Excited levels are always found with reorthogonalization
+        assert!(!solver.lanczos().reorthogonalize);
+        let solver: SolverArgs = toml::from_str("n_eigen = 2").unwrap();
+        assert!(solver.lanczos().reorthogonalize);
+    }
+
+    #[test]
+    fn test_cli_magnetization() {
+        let cli: Cli = Cli::parse_from([
+            "hubbies",
+            "magnetization",
+            "--field",
+            "0.5",
+            "--field-values",
+            "-1,0,1",
+        ]);
+        match cli.command {
+            Command::Magnetization {
+                model,
+                field_values,
+                ..
+            } => {
+                assert_eq!(0.5, model.field);
+                assert_eq!(vec![-1., 0., 1.], field_values);
+            }
+            _ => panic!("Expected the magnetization subcommand"),
+        }
+    }
+}
@@ -0,0 +1,123 @@
+// This module reads and writes the run configuration files.
+//
+// Instead of command-line flags, a run can be described inside a TOML (or
+// JSON, for files ending with '.json') configuration file containing the
+// subcommand and its arguments, for example
+//
+//      command = "sweep"
+//      u_values = [0.0, 2.0, 4.0, 8.0]
+//
+//      [model]
+//      lattice = "square:2x2"
+//      t = 1.0
+//
+//      [sectors]
+//      n_particles = 4
+//
+//      [solver]
+//      solver = "lanczos"
+//      n_eigen = 2
+//
+// Missing entries take the default values of the corresponding flags. Every
+// run (from flags or from a file) writes its complete configuration inside
+// 'run.toml' next to its data files, so that it can be reproduced with
+//
+//      hubbies run ./Data/run.toml
+
+use std::error::Error;
+use std::fs;
+
+use crate::cli::Command;
+
+/// Reads the subcommand described inside given configuration file.
+///
+/// Examples
+///
+/// ```rust
+/// let command: Command = read_config("./Data/run.toml").unwrap();
+/// ```
+pub fn read_config(path: &str) -> Result<Command, Box<dyn Error>> {
+    let contents: String = fs::read_to_string(path)?;
+    let command: Command = if path.ends_with(".json") {
+        serde_json::from_str(&contents)?
+    } else {
+        toml::from_str(&contents)?
+    };
+    match command {
+        Command::Run { .. } => Err("Configuration files cannot run other files".into()),
+        command => Ok(command),
+    }
+}
+
+/// Writes the complete configuration of a run (default values included)
+/// inside given file (TOML, or JSON for files ending with '.json').
+pub fn write_config(command: &Command, path: &str) -> Result<(), Box<dyn Error>> {
+    let contents: String = if path.ends_with(".json") {
+        serde_json::to_string_pretty(command)?
+    } else {
+        format!(
+            "# {} {} run configuration\n{}",
+            env!("CARGO_PKG_NAME"),
+            env!("CARGO_PKG_VERSION"),
+            toml::to_string(command)?
+        )
+    };
+    fs::write(path, contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use crate::cli::{Command, Solver};
+    use crate::config::{read_config, write_config};
+
+    #[test]
+    fn test_config_round_trip() {
+        // Writing a partial configuration inside a temporary file
+        let path = std::env::temp_dir().join("hubbies_test_config.toml");
+        let path: &str = path.to_str().unwrap();
+        std::fs::write(
+            path,
+            "command = \"sweep\"\nu_values = [0, 4.5]\n\n[model]\nlattice = \"chain:4\"\n\n\
+             [solver]\nsolver = \"lanczos\"\n",
+        )
+        .unwrap();
+
+        // Missing entries take the flags default values
+        let command: Command = read_config(path).unwrap();
+        match &command {
+            Command::Sweep {
+                model,
+                sectors,
+                solver,
+                t_values,
+                u_values,
+            } => {
+                assert!(t_values.is_empty());
+                assert_eq!(vec![0., 4.5], *u_values);
+                assert_eq!("chain:4", model.lattice);
+                assert_eq!(1., model.t);
+                assert_eq!(None, sectors.n_up);
+                assert_eq!(Solver::Lanczos, solver.solver);
+                assert_eq!(300, solver.max_iter);
+            }
+            _ => panic!("Expected the sweep subcommand"),
+        }
+
+        // Echoed configuration (JSON) describes the same run
+        let echo = std::env::temp_dir().join("hubbies_test_config.json");
+        let echo: &str = echo.to_str().unwrap();
+        write_config(&command, echo).unwrap();
+        assert_eq!(
+            format!("{:?}", command),
+            format!("{:?}", read_config(echo).unwrap())
+        );
+
+        // Same checks as the flags
+        std::fs::write(path, "command = \"sweep\"\n\n[solver]\nn_eigen = 0\n").unwrap();
+        assert!(read_config(path).is_err());
+    }
+}
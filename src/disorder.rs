@@ -0,0 +1,129 @@
+// This module defines the random disorder of the Anderson-Hubbard model.
+//
+// A Disorder object describes the strength of the random fluctuations of the
+// cluster parameters
+//
+//          e_i  ->  e_i + W_e x_i
+//          u_i  ->  u_i (1 + W_u y_i)
+//          t_ij ->  t_ij (1 + W_t z_ij)
+//
+// where the random numbers x, y, z follow either the box distribution (uniform
+// in [-1/2, 1/2]) or the standard normal distribution. Each realization of the
+// disorder is drawn from its own seed with a ChaCha generator, so that a
+// realization only depends on its seed (not on the number of threads or on the
+// other realizations) and any disordered cluster can be reproduced. The random
+// numbers are always drawn in the same order (energies, interactions, then
+// hoppings), even for vanishing widths.
+
+use clap::ValueEnum;
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use rand_distr::StandardNormal;
+use serde::{Deserialize, Serialize};
+
+use crate::lattice::Lattice;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Distribution {
+    /// Uniform in [-W/2, W/2]
+    Box,
+    /// Normal with standard deviation W
+    Gaussian,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Disorder {
+    // Public attributes
+    pub distribution: Distribution,
+    pub energies: f64,
+    pub interactions: f64,
+    pub hoppings: f64,
+}
+
+impl Disorder {
+    /// Draws a random number of given width from the distribution.
+    fn draw(&self, rng: &mut ChaCha8Rng, width: f64) -> f64 {
+        match self.distribution {
+            Distribution::Box => width * (rng.gen::<f64>() - 0.5),
+            Distribution::Gaussian => width * rng.sample::<f64, _>(StandardNormal),
+        }
+    }
+
+    /// Builds the realization of the disorder of given seed on a cluster.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let disorder = Disorder { distribution: Distribution::Box, energies: 2., interactions: 0., hoppings: 0. };
+    /// let lattice: Lattice = disorder.realize(&Lattice::chain(8), 42);
+    /// ```
+    pub fn realize(&self, lattice: &Lattice, seed: u64) -> Lattice {
+        let mut rng: ChaCha8Rng = ChaCha8Rng::seed_from_u64(seed);
+        let mut realization: Lattice = lattice.clone();
+        for e_i in realization.site_energies.iter_mut() {
+            *e_i += self.draw(&mut rng, self.energies);
+        }
+        for u_i in realization.site_interactions.iter_mut() {
+            *u_i *= 1. + self.draw(&mut rng, self.interactions);
+        }
+        for bond in realization.bonds.iter_mut() {
+            bond.hopping *= 1. + self.draw(&mut rng, self.hoppings);
+        }
+        realization
+    }
+}
+
+/// Computes the mean and the standard deviation of a list of values (both NaN
+/// for an empty list).
+pub fn mean_and_deviation(values: &[f64]) -> (f64, f64) {
+    let n: f64 = values.len() as f64;
+    let mean: f64 = values.iter().sum::<f64>() / n;
+    let variance: f64 = values.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / n;
+    (mean, variance.sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use crate::disorder::{mean_and_deviation, Disorder, Distribution};
+    use crate::lattice::Lattice;
+
+    #[test]
+    fn test_disorder_realization() {
+        let disorder: Disorder = Disorder {
+            distribution: Distribution::Box,
+            energies: 2.,
+            interactions: 0.,
+            hoppings: 0.5,
+        };
+        let ring: Lattice = Lattice::chain(8);
+        let realization: Lattice = disorder.realize(&ring, 7);
+
+        // Same seed gives the same cluster, another seed another one
+        assert_eq!(
+            realization.site_energies,
+            disorder.realize(&ring, 7).site_energies
+        );
+        assert_ne!(
+            realization.site_energies,
+            disorder.realize(&ring, 8).site_energies
+        );
+
+        // Widths of the fluctuations
+        assert!(realization.site_energies.iter().all(|e| e.abs() <= 1.));
+        assert_eq!(vec![1.; 8], realization.site_interactions);
+        assert!(realization
+            .bonds
+            .iter()
+            .all(|b| (b.hopping - 1.).abs() <= 0.25));
+    }
+
+    #[test]
+    fn test_mean_and_deviation() {
+        assert_eq!((2., 1.), mean_and_deviation(&[1., 3., 1., 3.]));
+        assert!(mean_and_deviation(&[]).0.is_nan());
+    }
+}
@@ -1,10 +1,151 @@
 // Contains useful functions to generate and manage csv data files.
 // This module is using the 'csv' crate.
+//
+// Eigenvalues are written one per row, labelled by the quantum numbers of the
+// block they belong to, so that data files can be filtered by sector:
+//
+//      n_up n_down dimension info level energy
+//      1 1 9 0 0 -1.4641016
+//      1 1 9 0 1 -1.0
+//      ...
+//
+// Eigenvectors (when requested) are written in the same way, one Fock state
+// amplitude per row:
+//
+//      n_up n_down level state amplitude
+//
+// Parameter sweeps prepend the model parameters of each point to the
+// eigenvalues rows:
+//
+//      t u n_up n_down dimension info level energy
+//
+// Grand-canonical ground states are written one per chemical potential:
+//
+//      mu n_up n_down filling energy
+//
+// Magnetization curves are written one ground state per magnetic field, with
+// the magnetization per site m = (N_up - N_down) / N_sites:
+//
+//      h n_up n_down magnetization energy
+//
+// Disorder runs write the lowest eigenvalues of each realization (labelled by
+// its index and seed) and twist runs the lowest eigenvalues of each twist
+// angles (in units of pi):
+//
+//      realization seed n_up n_down info level energy
+//      theta_1 theta_2 n_up n_down dimension info level energy
+//
+// along with their average over the realizations (or twists) and their
+// standard deviation:
+//
+//      n_up n_down level samples mean deviation
+//
+// Point group resolved spectra label the lowest eigenvalues of each block by
+// the irreducible representation of the point group and the degeneracy of its
+// levels (2 for the E representation, whose first partner only is kept):
+//
+//      irrep degeneracy n_up n_down dimension info level energy
+//
+// Momentum resolved spectra of translation invariant rings label the lowest
+// eigenvalues of each block by the momentum k = 2 pi m / n_sites, given by its
+// integer index m and by k in units of pi (k = 2 m / n_sites):
+//
+//      m k n_up n_down dimension info level energy
+//
+// Total spin resolved spectra add the total spin S (and/or the pseudospin
+// eta) of each level to the eigenvalues rows, and the multiplets of the
+// selected sectors are written one per row with their multiplicity (number of
+// degenerate levels):
+//
+//      n_up n_down dimension info level energy spin eta
+//      n_particles energy spin multiplicity
 
 use indicatif::{ProgressBar, ProgressStyle};
 use std::error::Error;
+use std::fmt::Display;
 use std::fs::OpenOptions;
 
+use crate::basis::State;
+use crate::sectors::Sector;
+
+/// Column names of the eigenvalues data files.
+pub const EIGENVALUES_HEADER: [&str; 6] =
+    ["n_up", "n_down", "dimension", "info", "level", "energy"];
+
+/// Column names of the parameter sweeps data files.
+pub const SWEEP_HEADER: [&str; 8] = [
+    "t",
+    "u",
+    "n_up",
+    "n_down",
+    "dimension",
+    "info",
+    "level",
+    "energy",
+];
+
+/// Column names of the grand-canonical data files.
+pub const GRAND_CANONICAL_HEADER: [&str; 5] = ["mu", "n_up", "n_down", "filling", "energy"];
+
+/// Column names of the magnetization curves data files.
+pub const MAGNETIZATION_HEADER: [&str; 5] = ["h", "n_up", "n_down", "magnetization", "energy"];
+
+/// Column names of the disorder realizations data files.
+pub const DISORDER_HEADER: [&str; 7] = [
+    "realization",
+    "seed",
+    "n_up",
+    "n_down",
+    "info",
+    "level",
+    "energy",
+];
+
+/// Column names of the twist angles data files.
+pub const TWIST_HEADER: [&str; 8] = [
+    "theta_1",
+    "theta_2",
+    "n_up",
+    "n_down",
+    "dimension",
+    "info",
+    "level",
+    "energy",
+];
+
+/// Column names of the momentum resolved data files.
+pub const MOMENTUM_HEADER: [&str; 8] = [
+    "m",
+    "k",
+    "n_up",
+    "n_down",
+    "dimension",
+    "info",
+    "level",
+    "energy",
+];
+
+/// Column names of the point group resolved data files.
+pub const SYMMETRY_HEADER: [&str; 8] = [
+    "irrep",
+    "degeneracy",
+    "n_up",
+    "n_down",
+    "dimension",
+    "info",
+    "level",
+    "energy",
+];
+
+/// Column names of the multiplets data files.
+pub const MULTIPLET_HEADER: [&str; 4] = ["n_particles", "energy", "spin", "multiplicity"];
+
+/// Column names of the disorder and twist averages data files.
+pub const AVERAGE_HEADER: [&str; 6] = ["n_up", "n_down", "level", "samples", "mean", "deviation"];
+
+/// Column names of the eigenvectors data files.
+pub const EIGENVECTORS_HEADER: [&str; 5] = ["n_up", "n_down", "level", "state", "amplitude"];
+
 /// Initialize a ProgressBar using 'indicatif' crate.
 ///
 /// # Examples
@@ -27,34 +168,6 @@ pub fn init_progress_bar(msg: String, iters: u64) -> ProgressBar {
     pb
 }
 
-/// Returns text file number of row(s).
-///
-/// # Examples
-///
-/// ```rust
-/// let path = String::from("./path/to/model");
-/// let nrows = file_lenght(&path).unwrap();
-/// println!("File {} has {} lines", &path, nrows);
-/// ```
-#[allow(dead_code)]
-fn file_length(file_path: &String, headers: bool) -> Result<u64, Box<dyn Error>> {
-    let rdr = csv::ReaderBuilder::new()
-        .has_headers(headers)
-        .from_path(file_path)
-        .unwrap();
-
-    let mut recs = rdr.into_records();
-    let mut nrows: u64 = 0;
-    loop {
-        let next_row = recs.reader().position().line();
-        if recs.next().is_none() {
-            break;
-        }
-        nrows = next_row;
-    }
-    Ok(nrows) // Removing headers row
-}
-
 /// Initialize file writter using 'csv' crate.
 ///
 /// # Examples
@@ -68,7 +181,7 @@ pub fn init_file_writter(path: &String, has_headers: bool) -> csv::Writer<std::f
         .truncate(true)
         .write(true)
         .create(true)
-        .open(&path)
+        .open(path)
         .unwrap();
 
     let writter = csv::WriterBuilder::new()
@@ -79,3 +192,326 @@ pub fn init_file_writter(path: &String, has_headers: bool) -> csv::Writer<std::f
 
     writter
 }
+
+/// Writes the header row of an eigenvalues data file.
+///
+/// # Examples
+///
+/// ```rust
+/// let path = String::from("path/to/file");
+/// let mut writter = init_file_writter(&path, false);
+/// write_eigenvalues_header(&mut writter).unwrap();
+/// ```
+pub fn write_eigenvalues_header(
+    writter: &mut csv::Writer<std::fs::File>,
+) -> Result<(), Box<dyn Error>> {
+    writter.write_record(EIGENVALUES_HEADER)?;
+    Ok(())
+}
+
+/// Writes the eigenvalues of one block of the hamiltonian, one row per
+/// eigenvalue, labelled with the sector quantum numbers, the dimension of the
+/// block and the LAPACK exit code.
+///
+/// # Examples
+///
+/// ```rust
+/// let sector: Sector = Sector::new(3, 1, 1);
+/// let pairs = model.get_sector_eigenpairs(1, 1, Eigenvectors::Skip);
+/// write_sector_eigenvalues(&mut writter, &sector, pairs.states.len(), pairs.info, &pairs.eigen_vals).unwrap();
+/// ```
+pub fn write_sector_eigenvalues<T: Display>(
+    writter: &mut csv::Writer<std::fs::File>,
+    sector: &Sector,
+    dimension: usize,
+    info: i32,
+    eigen_vals: &[T],
+) -> Result<(), Box<dyn Error>> {
+    for (level, energy) in eigen_vals.iter().enumerate() {
+        writter.write_record(&[
+            sector.n_up.to_string(),
+            sector.n_down.to_string(),
+            dimension.to_string(),
+            info.to_string(),
+            level.to_string(),
+            energy.to_string(),
+        ])?;
+    }
+    Ok(())
+}
+
+/// Writes the eigenvectors of one block of the hamiltonian, one row per Fock
+/// state amplitude, labelled with the sector quantum numbers and the level of
+/// the associated eigenvalue.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut writter = init_file_writter(&path, false);
+/// writter.write_record(EIGENVECTORS_HEADER).unwrap();
+/// write_sector_eigenvectors(&mut writter, &sector, &states, &eigen_vects).unwrap();
+/// ```
+pub fn write_sector_eigenvectors<T: Display>(
+    writter: &mut csv::Writer<std::fs::File>,
+    sector: &Sector,
+    states: &[State],
+    eigen_vects: &[Vec<T>],
+) -> Result<(), Box<dyn Error>> {
+    for (level, vector) in eigen_vects.iter().enumerate() {
+        for (state, amplitude) in states.iter().zip(vector) {
+            writter.write_record(&[
+                sector.n_up.to_string(),
+                sector.n_down.to_string(),
+                level.to_string(),
+                state.to_string(),
+                amplitude.to_string(),
+            ])?;
+        }
+    }
+    Ok(())
+}
+
+/// Writes the eigenvalues of one block of the hamiltonian for one point of a
+/// parameter sweep, one row per eigenvalue, labelled with the two parameters
+/// of the point ('t' and 'u', the twist angles or the momentum) followed by
+/// the columns of 'write_sector_eigenvalues'.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut writter = init_file_writter(&path, false);
+/// writter.write_record(SWEEP_HEADER).unwrap();
+/// write_sweep_eigenvalues(&mut writter, 1., 4., &sector, dim, info, &eigen_vals).unwrap();
+/// ```
+pub fn write_sweep_eigenvalues<T: Display>(
+    writter: &mut csv::Writer<std::fs::File>,
+    t: T,
+    u: T,
+    sector: &Sector,
+    dimension: usize,
+    info: i32,
+    eigen_vals: &[T],
+) -> Result<(), Box<dyn Error>> {
+    for (level, energy) in eigen_vals.iter().enumerate() {
+        writter.write_record(&[
+            t.to_string(),
+            u.to_string(),
+            sector.n_up.to_string(),
+            sector.n_down.to_string(),
+            dimension.to_string(),
+            info.to_string(),
+            level.to_string(),
+            energy.to_string(),
+        ])?;
+    }
+    Ok(())
+}
+
+/// Writes the grand-canonical ground state at given chemical potential, that
+/// is its sector, its filling (number of fermions per site) and its energy.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut writter = init_file_writter(&path, false);
+/// writter.write_record(GRAND_CANONICAL_HEADER).unwrap();
+/// write_grand_canonical_point(&mut writter, 0.5, &sector, energy).unwrap();
+/// ```
+pub fn write_grand_canonical_point<T: Display>(
+    writter: &mut csv::Writer<std::fs::File>,
+    mu: T,
+    sector: &Sector,
+    energy: T,
+) -> Result<(), Box<dyn Error>> {
+    let filling: f64 = (sector.n_up + sector.n_down) as f64 / sector.n_sites as f64;
+    writter.write_record(&[
+        mu.to_string(),
+        sector.n_up.to_string(),
+        sector.n_down.to_string(),
+        filling.to_string(),
+        energy.to_string(),
+    ])?;
+    Ok(())
+}
+
+/// Writes the ground state at given magnetic field, that is its sector, its
+/// magnetization per site and its energy.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut writter = init_file_writter(&path, false);
+/// writter.write_record(MAGNETIZATION_HEADER).unwrap();
+/// write_magnetization_point(&mut writter, 0.5, &sector, energy).unwrap();
+/// ```
+pub fn write_magnetization_point<T: Display>(
+    writter: &mut csv::Writer<std::fs::File>,
+    h: T,
+    sector: &Sector,
+    energy: T,
+) -> Result<(), Box<dyn Error>> {
+    let magnetization: f64 = (sector.n_up as f64 - sector.n_down as f64) / sector.n_sites as f64;
+    writter.write_record(&[
+        h.to_string(),
+        sector.n_up.to_string(),
+        sector.n_down.to_string(),
+        magnetization.to_string(),
+        energy.to_string(),
+    ])?;
+    Ok(())
+}
+
+/// Writes the lowest eigenvalues of one sector for one realization of the
+/// disorder, one row per eigenvalue.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut writter = init_file_writter(&path, false);
+/// writter.write_record(DISORDER_HEADER).unwrap();
+/// write_disorder_eigenvalues(&mut writter, 0, 42, &sector, info, &eigen_vals).unwrap();
+/// ```
+pub fn write_disorder_eigenvalues<T: Display>(
+    writter: &mut csv::Writer<std::fs::File>,
+    realization: usize,
+    seed: u64,
+    sector: &Sector,
+    info: i32,
+    eigen_vals: &[T],
+) -> Result<(), Box<dyn Error>> {
+    for (level, energy) in eigen_vals.iter().enumerate() {
+        writter.write_record(&[
+            realization.to_string(),
+            seed.to_string(),
+            sector.n_up.to_string(),
+            sector.n_down.to_string(),
+            info.to_string(),
+            level.to_string(),
+            energy.to_string(),
+        ])?;
+    }
+    Ok(())
+}
+
+/// Writes the average (and standard deviation) over the realizations of the
+/// disorder (or over the twist angles) of one eigenvalue of a sector.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut writter = init_file_writter(&path, false);
+/// writter.write_record(AVERAGE_HEADER).unwrap();
+/// write_level_average(&mut writter, &sector, 0, 100, mean, deviation).unwrap();
+/// ```
+pub fn write_level_average<T: Display>(
+    writter: &mut csv::Writer<std::fs::File>,
+    sector: &Sector,
+    level: usize,
+    samples: usize,
+    mean: T,
+    deviation: T,
+) -> Result<(), Box<dyn Error>> {
+    writter.write_record(&[
+        sector.n_up.to_string(),
+        sector.n_down.to_string(),
+        level.to_string(),
+        samples.to_string(),
+        mean.to_string(),
+        deviation.to_string(),
+    ])?;
+    Ok(())
+}
+
+/// Writes the eigenvalues of one block of the hamiltonian labelled with the
+/// irreducible representation of its states and the degeneracy of its levels,
+/// followed by the columns of 'write_sector_eigenvalues'.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut writter = init_file_writter(&path, false);
+/// writter.write_record(SYMMETRY_HEADER).unwrap();
+/// write_symmetry_eigenvalues(&mut writter, ("E", 2), &sector, dim, info, &eigen_vals).unwrap();
+/// ```
+pub fn write_symmetry_eigenvalues<T: Display>(
+    writter: &mut csv::Writer<std::fs::File>,
+    (irrep, degeneracy): (&str, usize),
+    sector: &Sector,
+    dimension: usize,
+    info: i32,
+    eigen_vals: &[T],
+) -> Result<(), Box<dyn Error>> {
+    for (level, energy) in eigen_vals.iter().enumerate() {
+        writter.write_record(&[
+            irrep.to_string(),
+            degeneracy.to_string(),
+            sector.n_up.to_string(),
+            sector.n_down.to_string(),
+            dimension.to_string(),
+            info.to_string(),
+            level.to_string(),
+            energy.to_string(),
+        ])?;
+    }
+    Ok(())
+}
+
+/// Writes the eigenvalues of one block of the hamiltonian along with the
+/// labels of each level (total spin, pseudospin), following the columns of
+/// 'write_sector_eigenvalues'.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut writter = init_file_writter(&path, false);
+/// writter.write_record(["n_up", "n_down", "dimension", "info", "level", "energy", "spin"]).unwrap();
+/// write_labelled_eigenvalues(&mut writter, &sector, dim, info, &eigen_vals, &labels).unwrap();
+/// ```
+pub fn write_labelled_eigenvalues<T: Display>(
+    writter: &mut csv::Writer<std::fs::File>,
+    sector: &Sector,
+    dimension: usize,
+    info: i32,
+    eigen_vals: &[T],
+    labels: &[Vec<f64>],
+) -> Result<(), Box<dyn Error>> {
+    for (level, (energy, level_labels)) in eigen_vals.iter().zip(labels).enumerate() {
+        let mut record: Vec<String> = vec![
+            sector.n_up.to_string(),
+            sector.n_down.to_string(),
+            dimension.to_string(),
+            info.to_string(),
+            level.to_string(),
+            energy.to_string(),
+        ];
+        record.extend(level_labels.iter().map(|label| label.to_string()));
+        writter.write_record(&record)?;
+    }
+    Ok(())
+}
+
+/// Writes one multiplet (number of fermions, energy, total spin and number of
+/// degenerate levels) as a row of the multiplets data file.
+///
+/// # Examples
+///
+/// ```rust
+/// let mut writter = init_file_writter(&path, false);
+/// writter.write_record(MULTIPLET_HEADER).unwrap();
+/// write_multiplet(&mut writter, 2, -0.8284, 0., 1).unwrap();
+/// ```
+pub fn write_multiplet<T: Display>(
+    writter: &mut csv::Writer<std::fs::File>,
+    n_particles: u32,
+    energy: T,
+    spin: f64,
+    multiplicity: usize,
+) -> Result<(), Box<dyn Error>> {
+    writter.write_record(&[
+        n_particles.to_string(),
+        energy.to_string(),
+        spin.to_string(),
+        multiplicity.to_string(),
+    ])?;
+    Ok(())
+}
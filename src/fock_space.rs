@@ -11,23 +11,62 @@
 //
 // According to this convention, we define struct's methods such as the
 // creation, anihilation and number operators from second quantization
-// formalism.
+// formalism. The orbitals are ordered from left to right in the ket (Jordan-
+// Wigner ordering), so that acting with an operator on orbital k gives a
+// fermionic sign (-1)^(number of fermions on the orbitals before k). This sign
+// is carried separately from the (unsigned) Fock state integer.
 //
 // Hubbard objects are defined using the number of sites in spins chain.
 // Then, we can compute the kinetic term and the interaction term of the
-// hamiltonian using FockState objects and properties.
+// hamiltonian using FockState objects and properties. The blocks of the
+// hamiltonian can either be discovered from any Fock state (discover_block)
+// or built directly for a given (N_up, N_down) sector (build_sector_block).
+//
+// BlockEigenpairs objects store the eigenvalues and the requested eigenvectors
+// of a block along with its basis, so that the eigenvectors components can be
+// mapped back to Fock states.
+//
+// On translation invariant rings, the sectors are further split into momentum
+// blocks spanned by Bloch states (build_momentum_block, see './src/momentum.rs').
+//
+// The sectors can also be split by the irreducible representations of a point
+// group of the cluster (build_symmetry_block, see './src/symmetry.rs'), and
+// the S_z = 0 sectors by the parity of their states under the spin inversion
+// (build_spin_inversion_block, see './src/spin_inversion.rs').
+//
+// For sectors too large to be stored as dense blocks, the hamiltonian can also
+// be applied to a vector without building the block (apply_hamiltonian), which
+// is used by the Lanczos solver (get_sector_lanczos).
 
-use itertools::Itertools;
+use lapack::c64;
+use num_traits::Num;
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use std::collections::HashMap;
+use std::ops::Neg;
 use std::vec;
 
-use crate::array_utils::lapack_diagonalization;
-use crate::file_utils::{init_file_writter, init_progress_bar};
+use crate::array_utils::{
+    lapack_diagonalization, lapack_eigenpairs, lapack_hermitian_diagonalization, Eigenvectors,
+    Scalar,
+};
+use crate::basis::{BlockBasis, State, StateBitSet, MAX_SITES};
+use crate::file_utils::{
+    init_file_writter, init_progress_bar, write_eigenvalues_header, write_sector_eigenvalues,
+    write_sector_eigenvectors, EIGENVECTORS_HEADER,
+};
+use crate::lanczos::{lanczos_eigenpairs, Lanczos};
+use crate::lattice::{Bond, Lattice};
+use crate::momentum::{representative, MomentumBasis};
+use crate::sectors::Sector;
+use crate::spin_inversion::SpinInversionBasis;
+use crate::symmetry::SymmetryBasis;
 
 #[derive(Debug)]
 pub struct FockState {
     // Public attributes
     pub n_sites: u32,
-    pub integer: i32,
+    pub integer: State,
 
     // Private attributes
     is_null: bool,
@@ -40,24 +79,41 @@ impl FockState {
     /// Examples
     ///
     /// ```rust
-    /// let state0 = FockState { n_sites: 2, integer: 5, is_null: false };
-    /// let state1 = FockState { n_sites: 2, integer: 6, is_null: false };
-    /// println!("{}", state0.scalar(state1));
+    /// let state0 = FockState { n_sites: 2, integer: 5, is_null: false, sign: 1 };
+    /// println!("{}", state0.scalar(6));
     /// ```
-    fn scalar(&self, state: i32) -> f32 {
+    fn scalar<T: Scalar>(&self, state: State) -> T {
         // Scalar product initialization
-        let mut scalar: f32 = 0.;
+        let mut scalar: T = T::zero();
 
         // Verifiying if orthogonal states
         if self.is_null {
-            scalar = 0.;
+            scalar = T::zero();
         } else if self.integer == state {
-            scalar = 1.;
+            scalar = T::from(self.sign).unwrap();
         }
         scalar
     }
 
-    /// Second quantization creation operator definition.
+    /// Computes the binary mask of given orbital.
+    fn mask(&self, index: u32) -> State {
+        1 << ((2 * self.n_sites - 1) - index)
+    }
+
+    /// Computes the Jordan-Wigner sign (-1)^n of the state, where n is the
+    /// number of fermions on the orbitals found before orbital 'index'.
+    fn jordan_wigner_sign(&self, index: u32) -> i32 {
+        // Orbitals before 'index' are the most significant bits
+        let position: u32 = (2 * self.n_sites - 1) - index;
+        if (self.integer >> position >> 1).count_ones() % 2 == 1 {
+            -1
+        } else {
+            1
+        }
+    }
+
+    /// Second quantization creation operator definition. The sign of the
+    /// state is flipped for each fermion found before orbital 'index'.
     ///
     /// Examples
     ///
@@ -66,23 +122,21 @@ impl FockState {
     /// state0.create(1);
     /// println!("{:?}", state0.integer_to_binary());
     /// ```
-    fn create(&mut self, index: i32) {
-        // Defining binary mask
-        let mask: i32 = 1 << ((2 * self.n_sites as i32 - 1) - index);
-        let mut abs_state: i32 = self.integer.abs();
-
+    fn create(&mut self, index: u32) {
         // Verifying if a fermion if already at position 'index' in state
-        if abs_state & mask != 0 || self.is_null {
+        let mask: State = self.mask(index);
+        if self.integer & mask != 0 || self.is_null {
             self.is_null = true;
 
         // Updating Fock state integer after creating fermion
         } else {
-            abs_state ^= mask;
+            self.sign *= self.jordan_wigner_sign(index);
+            self.integer ^= mask;
         }
-        self.integer = self.sign * abs_state
     }
 
-    /// Second quantization anihilation operator definition.
+    /// Second quantization anihilation operator definition. The sign of the
+    /// state is flipped for each fermion found before orbital 'index'.
     ///
     /// Examples
     ///
@@ -91,20 +145,17 @@ impl FockState {
     /// state0.destroy(1);
     /// println!("{:?}", state0.integer_to_binary());
     /// ```
-    fn destroy(&mut self, index: i32) {
-        // Defining binary mask
-        let mask: i32 = 1 << ((2 * self.n_sites as i32 - 1) - index);
-        let mut abs_state: i32 = self.integer.abs();
-
+    fn destroy(&mut self, index: u32) {
         // Verifying if no fermions are at position 'index' in state
-        if abs_state & mask == 0 || self.is_null {
+        let mask: State = self.mask(index);
+        if self.integer & mask == 0 || self.is_null {
             self.is_null = true;
 
         // Updating Fock state integer after destroying fermion
         } else {
-            abs_state ^= mask;
+            self.sign *= self.jordan_wigner_sign(index);
+            self.integer ^= mask;
         }
-        self.integer = self.sign * abs_state
     }
 
     /// Second quantization number operator definition.
@@ -118,208 +169,1092 @@ impl FockState {
     /// ```
     fn number(&mut self, index: u32) {
         // Verifiying if a fermion at site 'index' or if state is null
-        let mask: i32 = 1 << ((2 * self.n_sites - 1) - index);
-        if self.integer & mask == 0 || self.is_null {
+        if self.integer & self.mask(index) == 0 || self.is_null {
             self.is_null = true;
         }
     }
 }
 
-#[derive(Debug)]
-pub struct Hubbard {
-    // Public attributes
-    pub n_sites: u32,
-    pub t: f32,
-    pub u: f32,
+/// Applies the total spin operator S^2 = S^- S^+ + S_z^2 + S_z to given Fock
+/// state, where S^+ = sum_j c_j_up^dag c_j_down moves a spin down fermion to
+/// the spin up orbital of the same site.
+///
+/// It outputs a vector containing the linked states (including the initial
+/// state) along with the matrix elements (including the fermionic signs).
+///
+/// Examples
+///
+/// ```rust
+/// // | 1 0 ; 0 1 > -> | 1 0 ; 0 1 > - | 0 1 ; 1 0 >
+/// assert_eq!(vec![(6, -1.), (9, 1.)], spin_squared_term(9, 2));
+/// ```
+pub fn spin_squared_term(state_0: State, n_sites: u32) -> Vec<(State, f64)> {
+    // S_z^2 + S_z is diagonal
+    let n_up: f64 = (state_0 >> n_sites).count_ones() as f64;
+    let n_down: f64 = (state_0 & ((1 << n_sites) - 1)).count_ones() as f64;
+    let s_z: f64 = (n_up - n_down) / 2.;
+    let mut terms: Vec<(State, f64)> = vec![(state_0, s_z * s_z + s_z)];
+
+    // Spin flips S^- S^+ (site j flipped up, then site i flipped down)
+    for site_j in 0..n_sites {
+        for site_i in 0..n_sites {
+            let mut ket: FockState = FockState {
+                n_sites,
+                integer: state_0,
+                is_null: false,
+                sign: 1,
+            };
+            ket.destroy(site_j + n_sites);
+            ket.create(site_j);
+            ket.destroy(site_i);
+            ket.create(site_i + n_sites);
+            if ket.is_null {
+                continue;
+            }
+
+            // Push new state or add up (signed) amplitudes
+            match terms.iter_mut().find(|(state, _)| *state == ket.integer) {
+                Some(term) => term.1 += ket.sign as f64,
+                None => terms.push((ket.integer, ket.sign as f64)),
+            }
+        }
+    }
+    terms.retain(|(_, amplitude)| *amplitude != 0.);
+    terms.sort_by_key(|(state, _)| *state);
+    terms
 }
 
-impl Hubbard {
-    /// Computes 'on-site' interaction for given Fock State using second
-    /// quantization number operator.
-    pub fn interaction_term(&self, state_0: i32) -> f32 {
-        // Initializing matrix element
-        let mut coefficient: f32 = 0.;
+/// Applies the total pseudospin operator eta^2 = eta^- eta^+ + eta_z^2 + eta_z
+/// to given Fock state, where eta^+ = sum_j epsilon_j c_j_up^dag c_j_down^dag
+/// creates a doubly occupied site (with the sublattice signs 'signs' of the
+/// bipartite cluster) and eta_z = (N - n_sites) / 2.
+///
+/// It outputs a vector containing the linked states (including the initial
+/// state) along with the matrix elements (including the fermionic signs).
+///
+/// Examples
+///
+/// ```rust
+/// // Empty site: eta = 1/2 (eta^2 = 3/4)
+/// assert_eq!(vec![(0, 0.75)], eta_squared_term(0, 1, &[1]));
+/// ```
+pub fn eta_squared_term(state_0: State, n_sites: u32, signs: &[i32]) -> Vec<(State, f64)> {
+    // eta_z^2 + eta_z is diagonal
+    let eta_z: f64 = (state_0.count_ones() as f64 - n_sites as f64) / 2.;
+    let mut terms: Vec<(State, f64)> = vec![(state_0, eta_z * eta_z + eta_z)];
 
-        // Main loop over number of sites in the cluster (i)
-        for site in 0..self.n_sites {
-            // Initializing 'ket'
-            let mut ket_state: FockState = FockState {
-                n_sites: self.n_sites,
+    // Pair hoppings eta^- eta^+ (pair created on site j, destroyed on site i)
+    for site_j in 0..n_sites {
+        for site_i in 0..n_sites {
+            let mut ket: FockState = FockState {
+                n_sites,
                 integer: state_0,
                 is_null: false,
                 sign: 1,
             };
+            ket.create(site_j + n_sites);
+            ket.create(site_j);
+            ket.destroy(site_i);
+            ket.destroy(site_i + n_sites);
+            if ket.is_null {
+                continue;
+            }
+
+            // Push new state or add up (signed) amplitudes
+            let amplitude: f64 =
+                (ket.sign * signs[site_i as usize] * signs[site_j as usize]) as f64;
+            match terms.iter_mut().find(|(state, _)| *state == ket.integer) {
+                Some(term) => term.1 += amplitude,
+                None => terms.push((ket.integer, amplitude)),
+            }
+        }
+    }
+    terms.retain(|(_, amplitude)| *amplitude != 0.);
+    terms.sort_by_key(|(state, _)| *state);
+    terms
+}
+
+#[derive(Debug)]
+pub struct BlockEigenpairs<T: Scalar = f64> {
+    // Public attributes
+    pub sector: Sector,
+    pub states: Vec<State>,
+    pub info: i32,
+    pub eigen_vals: Vec<T>,
+    pub eigen_vects: Vec<Vec<T>>,
+}
+
+#[derive(Debug)]
+pub struct Hubbard<T: Scalar = f64> {
+    // Public attributes
+    pub lattice: Lattice,
+    pub t: T,
+    pub u: T,
+    pub v: T,
+    pub mu: T,
+    pub h: T,
+    pub particle_hole: bool,
+}
+
+impl<T: Scalar> Hubbard<T> {
+    /// Defines the Hubbard model on given cluster with nearest-neighbours
+    /// hopping amplitude 't' and on-site interaction 'u'. The extended
+    /// repulsion 'v', the chemical potential 'mu' and the magnetic field 'h'
+    /// are set to 0 and the interaction has its usual U n_up n_down form
+    /// ('particle_hole' set to false).
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let chain = Hubbard::new(Lattice::chain(6), 1., 2.);
+    /// let mut square = Hubbard::new(Lattice::square(4, 2), 1., 8.);
+    /// square.mu = 4.;
+    /// square.particle_hole = true;
+    /// ```
+    pub fn new(lattice: Lattice, t: T, u: T) -> Hubbard<T> {
+        assert!(
+            lattice.n_sites <= MAX_SITES,
+            "Fock states are limited to {} sites",
+            MAX_SITES
+        );
+        Hubbard {
+            lattice,
+            t,
+            u,
+            v: T::zero(),
+            mu: T::zero(),
+            h: T::zero(),
+            particle_hole: false,
+        }
+    }
 
+    /// Defines the same model (parameters and interaction form) on another
+    /// cluster, for example a disordered realization of the cluster.
+    pub fn with_lattice(&self, lattice: Lattice) -> Hubbard<T> {
+        Hubbard {
+            v: self.v,
+            mu: self.mu,
+            h: self.h,
+            particle_hole: self.particle_hole,
+            ..Hubbard::new(lattice, self.t, self.u)
+        }
+    }
+
+    /// Computes the occupation (0 or 1) of given orbital of a Fock State
+    /// using second quantization number operator.
+    fn occupation(&self, state_0: State, orbital: u32) -> T {
+        let mut ket_state: FockState = FockState {
+            n_sites: self.lattice.n_sites,
+            integer: state_0,
+            is_null: false,
+            sign: 1,
+        };
+        ket_state.number(orbital);
+        ket_state.scalar(state_0)
+    }
+
+    /// Computes 'on-site' interaction for given Fock State using second
+    /// quantization number operator (U_i = u times the interaction factor of
+    /// site i).
+    pub fn interaction_term(&self, state_0: State) -> T {
+        self.u * self.interaction_weight(state_0)
+    }
+
+    /// Computes the interaction term of given Fock State in units of 'u', that
+    /// is the sum of n_up n_down (or (n_up - 1/2)(n_down - 1/2) in the
+    /// particle-hole symmetric form) over the sites, each site being weighted
+    /// by its interaction factor.
+    pub fn interaction_weight(&self, state_0: State) -> T {
+        // Initializing matrix element
+        let mut coefficient: T = T::zero();
+        let shift: T = if self.particle_hole {
+            T::from(0.5).unwrap()
+        } else {
+            T::zero()
+        };
+
+        // Main loop over number of sites in the cluster (i)
+        for site in 0..self.lattice.n_sites {
             // Computing 'on site' interaction using number operator
-            ket_state.number(site);
-            ket_state.number(site + self.n_sites);
+            let n_up: T = self.occupation(state_0, site);
+            let n_down: T = self.occupation(state_0, site + self.lattice.n_sites);
 
             // Updating matrix element value
-            coefficient += self.u * ket_state.scalar(state_0);
+            let u_i: T = T::from(self.lattice.site_interactions[site as usize]).unwrap();
+            coefficient = coefficient + u_i * (n_up - shift) * (n_down - shift);
         }
         coefficient
     }
 
-    /// Computes first neighbours hoppings for given Fock State using second
-    /// quantization operators.
+    /// Computes the extended interaction V sum_<ij> v_ij n_i n_j along the
+    /// bonds of the cluster for given Fock State (V_ij = v times the
+    /// repulsion factor of the bond).
+    pub fn extended_term(&self, state_0: State) -> T {
+        self.v * self.extended_weight(state_0)
+    }
+
+    /// Computes the extended interaction of given Fock State in units of 'v',
+    /// that is the sum of n_i n_j (or (n_i - 1)(n_j - 1) in the particle-hole
+    /// symmetric form) over the bonds, each bond being weighted by its
+    /// repulsion factor.
+    pub fn extended_weight(&self, state_0: State) -> T {
+        // Initializing matrix element
+        let mut coefficient: T = T::zero();
+        let shift: T = if self.particle_hole {
+            T::one()
+        } else {
+            T::zero()
+        };
+        let density = |site: u32| -> T {
+            self.occupation(state_0, site) + self.occupation(state_0, site + self.lattice.n_sites)
+        };
+
+        // Main loop over the bonds of the cluster (i, j), without the bonds
+        // removed by open boundaries
+        for bond in self.lattice.bonds.iter() {
+            if bond.repulsion == 0. || self.lattice.bond_phase(bond).is_none() {
+                continue;
+            }
+            let (site_i, site_j) = bond.sites;
+            let v_ij: T = T::from(bond.repulsion).unwrap();
+            coefficient =
+                coefficient + v_ij * (density(site_i) - shift) * (density(site_j) - shift);
+        }
+        coefficient
+    }
+
+    /// Computes the on-site energies term for given Fock State using second
+    /// quantization number operator.
+    pub fn potential_term(&self, state_0: State) -> T {
+        // Initializing matrix element
+        let mut coefficient: T = T::zero();
+
+        // Main loop over the orbitals of the cluster (spin up & down)
+        for orbital in 0..2 * self.lattice.n_sites {
+            let site: usize = (orbital % self.lattice.n_sites) as usize;
+            let e_i: T = T::from(self.lattice.site_energies[site]).unwrap();
+            coefficient = coefficient + e_i * self.occupation(state_0, orbital);
+        }
+        coefficient
+    }
+
+    /// Computes the chemical potential shift -mu * N of given sector. It is
+    /// the same for every Fock State of the sector.
+    pub fn chemical_shift(&self, sector: &Sector) -> T {
+        -self.mu * T::from(sector.n_up + sector.n_down).unwrap()
+    }
+
+    /// Computes the Zeeman shift -h * (N_up - N_down) of given sector. It is
+    /// the same for every Fock State of the sector.
+    pub fn zeeman_shift(&self, sector: &Sector) -> T {
+        let n_up: T = T::from(sector.n_up).unwrap();
+        let n_down: T = T::from(sector.n_down).unwrap();
+        -self.h * (n_up - n_down)
+    }
+
+    /// Computes the local magnetic fields term -sum_i h_i (n_i_up - n_i_down)
+    /// for given Fock State using second quantization number operator.
+    pub fn site_fields_term(&self, state_0: State) -> T {
+        // Initializing matrix element
+        let mut coefficient: T = T::zero();
+
+        // Main loop over number of sites in the cluster (i)
+        for site in 0..self.lattice.n_sites {
+            let n_up: T = self.occupation(state_0, site);
+            let n_down: T = self.occupation(state_0, site + self.lattice.n_sites);
+            let h_i: T = T::from(self.lattice.site_fields[site as usize]).unwrap();
+            coefficient = coefficient - h_i * (n_up - n_down);
+        }
+        coefficient
+    }
+
+    /// Computes the one-body diagonal terms of the hamiltonian for given Fock
+    /// State (on-site energies, chemical potential and magnetic fields).
+    pub fn field_term(&self, state_0: State) -> T {
+        let sector: Sector = Sector::of_state(self.lattice.n_sites, state_0);
+        self.potential_term(state_0)
+            + self.site_fields_term(state_0)
+            + self.chemical_shift(&sector)
+            + self.zeeman_shift(&sector)
+    }
+
+    /// Computes the diagonal matrix element of the hamiltonian for given Fock
+    /// State (on-site and extended interactions, on-site energies, chemical
+    /// potential and magnetic fields).
+    pub fn diagonal_term(&self, state_0: State) -> T {
+        self.interaction_term(state_0) + self.extended_term(state_0) + self.field_term(state_0)
+    }
+
+    /// Computes the shift D(1 - n) - D(n) of the diagonal terms of given Fock
+    /// state under the particle-hole transformation (every orbital of the
+    /// state emptied or filled).
+    pub fn particle_hole_shift(&self, state_0: State) -> T {
+        let filled: State = State::MAX >> (State::BITS - 2 * self.lattice.n_sites);
+        self.diagonal_term(state_0 ^ filled) - self.diagonal_term(state_0)
+    }
+
+    /// Checks if the particle-hole transformation c_i -> epsilon_i c_i^dag maps
+    /// the sector (N_up, N_down) onto (n_sites - N_up, n_sites - N_down) up to
+    /// a constant: the hoppings must be real and only link the two sublattices
+    /// of a bipartite cluster, and the shift of the diagonal terms must only
+    /// depend on the number of fermions (no magnetic fields, uniform on-site
+    /// energies and, without the particle-hole symmetric interactions, uniform
+    /// interactions and repulsions).
+    ///
+    /// Examples
     ///
-    /// It outputs a vector containing linked states for given initial Fock state.
-    pub fn kinetic_term(&self, state_0: i32) -> Vec<i32> {
-        // Initializing subspace states
-        let mut sub_states: Vec<i32> = Vec::new();
-        let sites: Vec<i32> = (0..self.n_sites as i32).collect();
+    /// ```rust
+    /// let mut model = Hubbard::new(Lattice::chain(6), 1., 4.);
+    /// assert!(model.is_particle_hole_symmetric());
+    /// model.h = 0.5;
+    /// assert!(!model.is_particle_hole_symmetric());
+    /// ```
+    pub fn is_particle_hole_symmetric(&self) -> bool {
+        if !self.lattice.is_real() || self.lattice.sublattice_signs().is_none() {
+            return false;
+        }
 
-        // Main loop over number of sites in the cluster (i, j)
-        for (idx, perm) in sites.iter().permutations(2).unique().enumerate() {
-            // Defining neighbours coordinates using permutations
-            let site_i: i32 = *perm[0];
-            let site_j: i32 = *perm[1];
-            let bound: i32 = self.n_sites as i32 - 1;
+        // The shift D(1 - n) - D(n) is affine in the occupations n: it must
+        // have the same slope along every orbital
+        let vacuum: T = self.particle_hole_shift(0);
+        let slopes: Vec<T> = (0..2 * self.lattice.n_sites)
+            .map(|orbital| self.particle_hole_shift(1 << orbital) - vacuum)
+            .collect();
+        let tolerance: T = T::epsilon().sqrt();
+        slopes.iter().all(|&a| (a - slopes[0]).abs() < tolerance)
+    }
 
-            // Initializing 'kets' (spin up & down)
-            let mut ket_up: FockState = FockState {
-                n_sites: self.n_sites,
-                integer: state_0,
-                is_null: false,
-                sign: state_0.signum(),
-            };
+    /// Checks if the total pseudospin eta^2 of the eta-pairing operators
+    /// commutes with the hamiltonian: the hoppings must be real and only link
+    /// the two sublattices of a bipartite cluster, the on-site energies and
+    /// interactions must be uniform and the extended repulsion must vanish.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let mut model = Hubbard::new(Lattice::chain(6), 1., 4.);
+    /// assert!(model.is_eta_pairing_symmetric());
+    /// model.v = 1.;
+    /// assert!(!model.is_eta_pairing_symmetric());
+    /// ```
+    pub fn is_eta_pairing_symmetric(&self) -> bool {
+        if !self.lattice.is_real() || self.lattice.sublattice_signs().is_none() {
+            return false;
+        }
+        let uniform = |values: &[f64]| values.windows(2).all(|pair| pair[0] == pair[1]);
+        let extended: bool =
+            !self.v.is_zero() && self.lattice.bonds.iter().any(|b| b.repulsion != 0.);
+        !extended
+            && uniform(&self.lattice.site_energies)
+            && uniform(&self.lattice.site_interactions)
+    }
 
-            let mut ket_down: FockState = FockState {
-                n_sites: self.n_sites,
-                integer: state_0,
-                is_null: false,
-                sign: state_0.signum(),
-            };
+    /// Computes the hoppings along the bonds of the cluster for given Fock
+    /// State using second quantization operators.
+    ///
+    /// It outputs a vector containing linked states for given initial Fock state
+    /// along with the matrix element t * t_ij (including the fermionic sign and
+    /// summed over the bonds linking the same states).
+    pub fn kinetic_term(&self, state_0: State) -> Vec<(State, T)> {
+        self.hopping_term(state_0)
+            .into_iter()
+            .map(|(state, amplitude)| (state, self.t * amplitude))
+            .collect()
+    }
+
+    /// Computes the hoppings along the bonds of the cluster for given Fock
+    /// State in units of 't'.
+    ///
+    /// It outputs a vector containing linked states for given initial Fock state
+    /// along with the matrix element t_ij (including the fermionic sign, the
+    /// sign of antiperiodic boundaries and summed over the bonds linking the
+    /// same states). Panics if the boundaries make the hoppings complex (see
+    /// 'complex_hopping_term').
+    pub fn hopping_term(&self, state_0: State) -> Vec<(State, T)> {
+        self.bond_hoppings(state_0, |bond, _| {
+            let phase: f64 = self.lattice.bond_phase(bond)?;
+            assert!(
+                phase.sin().abs() < 1e-12,
+                "Twisted boundaries need the complex hermitian blocks"
+            );
+            Some(T::from(bond.hopping * phase.cos()).unwrap())
+        })
+    }
 
-            // Kinetic term (spin up & down)
-            let ket_up_int: i32 = ket_up.integer;
-            ket_up.destroy(site_j);
-            ket_up.create(site_i);
-
-            let ket_down_int: i32 = ket_down.integer;
-            ket_down.destroy(site_j + self.n_sites as i32);
-            ket_down.create(site_i + self.n_sites as i32);
-
-            // Push new state inside subspace if not already there
-            if !sub_states.contains(&ket_up.integer) && !ket_up.is_null {
-                let mut sign_up: i32 = 1;
-                // Verifying if current sites correspond to boundary conditions
-                if idx == (bound - 1) as usize || idx == (bound * bound) as usize {
-                    // Spin up phase adjustement
-                    let ket_up_boundary: u32 = (ket_up_int >> self.n_sites).count_ones() - 1;
-                    if !(ket_up_boundary % 2 == 0) {
-                        sign_up = -1
+    /// Computes the (complex) hoppings along the bonds of the cluster for given
+    /// Fock State in units of 't', a fermion hopping along a bond picking up
+    /// the phase of the twisted boundaries it crosses.
+    pub fn complex_hopping_term(&self, state_0: State) -> Vec<(State, c64)> {
+        self.bond_hoppings(state_0, |bond, forward| {
+            let phase: f64 = self.lattice.bond_phase(bond)?;
+            let phase: f64 = if forward { phase } else { -phase };
+            Some(c64::new(phase.cos(), phase.sin()) * bond.hopping)
+        })
+    }
+
+    /// Applies the hoppings along the bonds of the cluster to given Fock State,
+    /// the amplitude of each bond being given by 'bond_amplitude' (None for
+    /// removed bonds) for a fermion hopping from its first site to its second
+    /// site ('forward') or backward.
+    fn bond_hoppings<A, F>(&self, state_0: State, bond_amplitude: F) -> Vec<(State, A)>
+    where
+        A: Num + Neg<Output = A> + Copy,
+        F: Fn(&Bond, bool) -> Option<A>,
+    {
+        // Initializing subspace states (and the index of their integers)
+        let mut sub_states: Vec<(State, A)> = Vec::new();
+        let mut linked: HashMap<State, usize> = HashMap::new();
+        let n_sites: u32 = self.lattice.n_sites;
+
+        // Main loop over the bonds of the cluster (i, j) in both directions
+        for bond in self.lattice.bonds.iter() {
+            let (site_a, site_b): (u32, u32) = bond.sites;
+            for (site_i, site_j, forward) in [(site_a, site_b, false), (site_b, site_a, true)] {
+                let hopping: A = match bond_amplitude(bond, forward) {
+                    Some(hopping) => hopping,
+                    None => continue,
+                };
+
+                // Spin up & down orbitals
+                for (orbital_i, orbital_j) in
+                    [(site_i, site_j), (site_i + n_sites, site_j + n_sites)]
+                {
+                    // Initializing 'ket'
+                    let mut ket: FockState = FockState {
+                        n_sites,
+                        integer: state_0,
+                        is_null: false,
+                        sign: 1,
+                    };
+
+                    // Kinetic term (Jordan-Wigner sign given by the operators)
+                    ket.destroy(orbital_j);
+                    ket.create(orbital_i);
+                    if ket.is_null {
+                        continue;
                     }
-                }
-                sub_states.push(sign_up * ket_up.integer)
-            }
-            if !sub_states.contains(&ket_down.integer) && !ket_down.is_null {
-                let mut sign_down: i32 = 1;
-                // Verifying if current sites correspond to boundary conditions
-                if idx == (bound - 1) as usize || idx == (bound * bound) as usize {
-                    // Spin down phase ajustement
-                    let down_temp_int: i32 = ket_down_int;
-                    let shifted_int: i32 =
-                        (down_temp_int >> self.n_sites as i32) << self.n_sites as i32;
-                    let ket_down_boundary: u32 = (ket_down_int - shifted_int).count_ones() - 1;
-                    if !(ket_down_boundary % 2 == 0) {
-                        sign_down = -1
+
+                    // Push new state or add up (signed) amplitudes
+                    let amplitude: A = if ket.sign < 0 { -hopping } else { hopping };
+                    if let Some(&idx) = linked.get(&ket.integer) {
+                        sub_states[idx].1 = sub_states[idx].1 + amplitude;
+                    } else {
+                        linked.insert(ket.integer, sub_states.len());
+                        sub_states.push((ket.integer, amplitude))
                     }
                 }
-                sub_states.push(sign_down * ket_down.integer)
             }
         }
-        sub_states.sort_by_key(|i| i.abs());
+        sub_states.retain(|(_, amplitude)| !amplitude.is_zero());
+        sub_states.sort_by_key(|(state, _)| *state);
         sub_states
     }
 
-    /// Finds a block of the Hubbard hamiltonian using one Fock State at a time.
-    ///
-    /// It outputs a tuple in which we found all the states involved in the current
-    /// block  and the matrix element of the block sorted 'column-wise' as LAPACK would
-    /// recommend.
-    pub fn find_sub_block(&self, state: i32) -> (Vec<i32>, Vec<f32>) {
-        // Test index for new substates
-        let mut idx: u32 = 0;
-        let mut sub_states: Vec<i32> = vec![state];
+    /// Outputs the states linked to given Fock state by the hoppings, using
+    /// the complex hoppings if the boundaries are twisted ('real' is false).
+    fn linked_states(&self, state_0: State, real: bool) -> Vec<State> {
+        if real {
+            self.hopping_term(state_0)
+                .into_iter()
+                .map(|(s, _)| s)
+                .collect()
+        } else {
+            self.complex_hopping_term(state_0)
+                .into_iter()
+                .map(|(s, _)| s)
+                .collect()
+        }
+    }
 
-        // Matrix elements array
-        let mut elems: Vec<f32> = Vec::new();
+    /// Computes the matrix elements of the block spanned by given states
+    /// (sorted in increasing order), sorted 'column-wise' as LAPACK would
+    /// recommend. The row index of the states linked by hoppings is found using
+    /// the 'row_index' lookup.
+    fn block_elements<F>(&self, sub_states: &[State], row_index: F) -> Vec<T>
+    where
+        F: Fn(State) -> Option<usize>,
+    {
+        // Matrix elements array (upper triangle)
+        let dimension: usize = sub_states.len();
+        let mut elems: Vec<T> = Vec::with_capacity(dimension * (dimension + 1) / 2);
 
-        // Continue loop until substates aren't new
-        while idx < sub_states.len() as u32 {
-            // Defining current sub state
-            let current_state: i32 = sub_states[idx as usize];
+        // Loop over the columns of the block
+        for (col, &current_state) in sub_states.iter().enumerate() {
+            let mut column: Vec<T> = vec![T::zero(); col + 1];
 
-            // Find first hopping states
-            let new_states: Vec<i32> = self.kinetic_term(current_state);
-            let mut filtered: Vec<i32> = new_states.iter().map(|&i| i.abs()).collect();
-            filtered.retain(|i: &i32| !sub_states.contains(&i));
-            sub_states.append(&mut filtered);
+            // Kinetic terms
+            for (linked_state, amplitude) in self.kinetic_term(current_state) {
+                if let Some(row) = row_index(linked_state) {
+                    if row < col {
+                        column[row] = amplitude;
+                    }
+                }
+            }
+
+            // On-site interaction & energies coefficient
+            column[col] = self.diagonal_term(current_state);
+            elems.append(&mut column);
+        }
+        elems
+    }
+
+    /// Computes the complex matrix elements of the block spanned by given
+    /// states (twisted boundaries), sorted 'column-wise' like 'block_elements'.
+    fn complex_block_elements<F>(&self, sub_states: &[State], row_index: F) -> Vec<c64>
+    where
+        F: Fn(State) -> Option<usize>,
+    {
+        // Matrix elements array (upper triangle)
+        let dimension: usize = sub_states.len();
+        let t: f64 = self.t.to_f64().unwrap();
+        let mut elems: Vec<c64> = Vec::with_capacity(dimension * (dimension + 1) / 2);
+
+        // Loop over the columns of the block
+        for (col, &current_state) in sub_states.iter().enumerate() {
+            let mut column: Vec<c64> = vec![c64::new(0., 0.); col + 1];
 
             // Kinetic terms
-            let states_copy: Vec<&i32> =
-                sub_states.iter().filter(|i| i < &&current_state).collect();
-            for sub_state in states_copy {
-                // Verifying for any positive integer corresponding to add 't'
-                if new_states.iter().any(|&i| i == *sub_state) {
-                    elems.push(self.t);
-                // Verifying for any negative integer corresponding to add 't'
-                } else if new_states.iter().any(|&i| i == -sub_state) {
-                    elems.push(-self.t)
-                } else {
-                    elems.push(0.)
+            for (linked_state, amplitude) in self.complex_hopping_term(current_state) {
+                if let Some(row) = row_index(linked_state) {
+                    if row < col {
+                        column[row] = amplitude * t;
+                    }
                 }
             }
 
-            // On-site interaction coefficient
-            elems.push(self.interaction_term(current_state.abs()));
+            // On-site interaction & energies coefficient
+            column[col] = c64::new(self.diagonal_term(current_state).to_f64().unwrap(), 0.);
+            elems.append(&mut column);
+        }
+        elems
+    }
 
-            // Updating array parser
-            sub_states.sort_by_key(|i| i.abs());
+    /// Discovers all the states of the block containing given Fock state by
+    /// applying the kinetic term until no new states are found.
+    ///
+    /// It outputs the basis of the block sorted in increasing order.
+    pub fn discover_block(&self, state: State) -> BlockBasis {
+        // Test index for new substates
+        let mut idx: usize = 0;
+        let mut basis: BlockBasis = BlockBasis::default();
+        let real: bool = self.lattice.is_real();
+        basis.push(state);
+
+        // Continue loop until substates aren't new
+        while idx < basis.len() {
+            // Find first hopping states from current sub state
+            let current_state: State = basis.states[idx];
+            for linked_state in self.linked_states(current_state, real) {
+                basis.push(linked_state);
+            }
             idx += 1;
         }
+        basis.sort();
+        basis
+    }
+
+    /// Builds the block of the Hubbard hamiltonian associated with given
+    /// (N_up, N_down) sector without visiting the rest of the Fock space.
+    ///
+    /// It outputs a tuple containing the sector basis (increasing order) and
+    /// the matrix elements of the block sorted 'column-wise' as LAPACK would
+    /// recommend.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let model = Hubbard::new(Lattice::chain(6), 1., 2.);
+    /// let (states, elems) = model.build_sector_block(&Sector::new(6, 3, 3));
+    /// ```
+    pub fn build_sector_block(&self, sector: &Sector) -> (Vec<State>, Vec<T>) {
+        // Sector basis enumerated using combinatorial unranking
+        let sub_states: Vec<State> = sector.basis();
+
+        // Matrix elements using ranked index of the linked states
+        let elems: Vec<T> = self.block_elements(&sub_states, |s| Some(sector.index(s)));
+        (sub_states, elems)
+    }
+
+    /// Builds the complex hermitian block of the Hubbard hamiltonian associated
+    /// with given (N_up, N_down) sector (twisted boundaries).
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let mut lattice = Lattice::chain(6);
+    /// lattice.boundaries[0] = Boundary::Twisted(0.5);
+    /// let model = Hubbard::new(lattice, 1., 2.);
+    /// let (states, elems) = model.build_complex_sector_block(&Sector::new(6, 3, 3));
+    /// ```
+    pub fn build_complex_sector_block(&self, sector: &Sector) -> (Vec<State>, Vec<c64>) {
+        let sub_states: Vec<State> = sector.basis();
+        let elems: Vec<c64> = self.complex_block_elements(&sub_states, |s| Some(sector.index(s)));
         (sub_states, elems)
     }
 
+    /// Outputs the eigenvalues of the complex hermitian block of given sector
+    /// using LAPACK 'zhpevd' (in double precision).
+    fn complex_sector_eigenvalues(&self, sector: &Sector) -> (i32, Vec<T>) {
+        let (_sub_states, matrix_elems) = self.build_complex_sector_block(sector);
+        let (info, eigen_vals) = lapack_hermitian_diagonalization(matrix_elems);
+        (
+            info,
+            eigen_vals
+                .into_iter()
+                .map(|e| T::from(e).unwrap())
+                .collect(),
+        )
+    }
+
+    /// Builds the complex hermitian block of the hamiltonian spanned by the
+    /// Bloch states of given momentum basis on a translation invariant ring
+    /// (see './src/momentum.rs'), sorted 'column-wise' as LAPACK would
+    /// recommend.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let model = Hubbard::new(Lattice::chain(6), 1., 2.);
+    /// let elems = model.build_momentum_block(&MomentumBasis::new(Sector::new(6, 3, 3), 1));
+    /// ```
+    pub fn build_momentum_block(&self, basis: &MomentumBasis) -> Vec<c64> {
+        // Matrix elements array (upper triangle)
+        let dimension: usize = basis.dimension();
+        let n_sites: u32 = self.lattice.n_sites;
+        let k: f64 = basis.k();
+        let mut elems: Vec<c64> = Vec::with_capacity(dimension * (dimension + 1) / 2);
+
+        // Loop over the columns of the block (orbit representatives)
+        for (col, (&current_state, &p_col)) in basis
+            .representatives
+            .iter()
+            .zip(basis.periods.iter())
+            .enumerate()
+        {
+            let mut column: Vec<c64> = vec![c64::new(0., 0.); col + 1];
+
+            // Kinetic terms, the linked states being c_s T^d_s | r' >
+            for (linked_state, amplitude) in self.kinetic_term(current_state) {
+                let (linked_rep, distance, sign) = representative(linked_state, n_sites);
+                if let Some(row) = basis.index_of(linked_rep) {
+                    if row <= col {
+                        let ratio: f64 = (p_col as f64 / basis.periods[row] as f64).sqrt();
+                        let phase: f64 = -k * distance as f64;
+                        let weight: f64 = amplitude.to_f64().unwrap() * sign as f64 * ratio;
+                        column[row] += c64::new(phase.cos(), phase.sin()) * weight;
+                    }
+                }
+            }
+
+            // On-site interaction & energies coefficient (translation invariant)
+            column[col] += c64::new(self.diagonal_term(current_state).to_f64().unwrap(), 0.);
+            elems.append(&mut column);
+        }
+        elems
+    }
+
+    /// Outputs the eigenvalues of the block of given momentum basis on a
+    /// translation invariant ring using LAPACK 'zhpevd' (in double precision).
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let model = Hubbard::new(Lattice::chain(6), 1., 2.);
+    /// let basis = MomentumBasis::new(Sector::new(6, 3, 3), 0);
+    /// let (info, eigen_vals) = model.get_momentum_eigenvalues(&basis);
+    /// ```
+    pub fn get_momentum_eigenvalues(&self, basis: &MomentumBasis) -> (i32, Vec<T>) {
+        assert!(
+            self.lattice.is_translation_invariant(),
+            "Momentum sectors need a translation invariant ring"
+        );
+        let matrix_elems: Vec<c64> = self.build_momentum_block(basis);
+        let (info, eigen_vals) = lapack_hermitian_diagonalization(matrix_elems);
+        (
+            info,
+            eigen_vals
+                .into_iter()
+                .map(|e| T::from(e).unwrap())
+                .collect(),
+        )
+    }
+
+    /// Builds the block of the hamiltonian spanned by the states of given
+    /// irreducible representation of a point group (see './src/symmetry.rs'),
+    /// sorted 'column-wise' as LAPACK would recommend.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let model = Hubbard::new(Lattice::square(2, 2), 1., 4.);
+    /// let symmetry = Symmetry::new(PointGroup::C4v, &model.lattice).unwrap();
+    /// let elems = model.build_symmetry_block(&SymmetryBasis::new(&symmetry, Sector::new(4, 2, 2), 0));
+    /// ```
+    pub fn build_symmetry_block(&self, basis: &SymmetryBasis) -> Vec<T> {
+        // Matrix elements array (upper triangle)
+        let dimension: usize = basis.dimension();
+        let mut elems: Vec<T> = Vec::with_capacity(dimension * (dimension + 1) / 2);
+
+        // Loop over the columns of the block (projected states)
+        for (col, vector) in basis.vectors.iter().enumerate() {
+            // Hamiltonian applied to the projected state (sorted components)
+            let mut linked: HashMap<State, T> = HashMap::new();
+            for &(state, amplitude) in vector.iter() {
+                let amplitude: T = T::from(amplitude).unwrap();
+                let diagonal: T = self.diagonal_term(state);
+                for (linked_state, element) in self
+                    .kinetic_term(state)
+                    .into_iter()
+                    .chain([(state, diagonal)])
+                {
+                    let coefficient: &mut T = linked.entry(linked_state).or_insert(T::zero());
+                    *coefficient = *coefficient + element * amplitude;
+                }
+            }
+            let mut linked: Vec<(State, T)> = linked.into_iter().collect();
+            linked.sort_by_key(|(state, _)| *state);
+
+            // Overlaps with the projected states of the previous columns
+            let mut column: Vec<T> = vec![T::zero(); col + 1];
+            for (state, coefficient) in linked {
+                for &(row, amplitude) in basis.overlaps(state) {
+                    if row <= col {
+                        column[row] = column[row] + T::from(amplitude).unwrap() * coefficient;
+                    }
+                }
+            }
+            elems.append(&mut column);
+        }
+        elems
+    }
+
+    /// Outputs the eigenvalues of the block of given irreducible representation
+    /// of a point group using LAPACK 'sspevd' (or 'dspevd').
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let model = Hubbard::new(Lattice::chain(6), 1., 4.);
+    /// let symmetry = Symmetry::new(PointGroup::Reflection, &model.lattice).unwrap();
+    /// let basis = SymmetryBasis::new(&symmetry, Sector::new(6, 3, 3), 1);
+    /// let (info, eigen_vals) = model.get_symmetry_eigenvalues(&basis);
+    /// ```
+    pub fn get_symmetry_eigenvalues(&self, basis: &SymmetryBasis) -> (i32, Vec<T>) {
+        lapack_diagonalization(self.build_symmetry_block(basis))
+    }
+
+    /// Builds the block of the hamiltonian spanned by the even or odd states
+    /// of a S_z = 0 sector under the spin inversion (see
+    /// './src/spin_inversion.rs'), sorted 'column-wise' as LAPACK would
+    /// recommend.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let model = Hubbard::new(Lattice::chain(6), 1., 4.);
+    /// let elems = model.build_spin_inversion_block(&SpinInversionBasis::new(Sector::new(6, 3, 3), 1));
+    /// ```
+    pub fn build_spin_inversion_block(&self, basis: &SpinInversionBasis) -> Vec<T> {
+        // Matrix elements array (upper triangle)
+        let dimension: usize = basis.dimension();
+        let mut elems: Vec<T> = Vec::with_capacity(dimension * (dimension + 1) / 2);
+
+        // Loop over the columns of the block (pair representatives)
+        for (col, &current_state) in basis.representatives.iter().enumerate() {
+            let mut column: Vec<T> = vec![T::zero(); col + 1];
+            let size: T = T::from(basis.pair_size(col)).unwrap();
+
+            // Kinetic terms, the linked states being | r' > or | r'' >
+            for (linked_state, amplitude) in self.kinetic_term(current_state) {
+                if let Some((row, sign)) = basis.locate(linked_state) {
+                    if row <= col {
+                        let ratio: T = (size / T::from(basis.pair_size(row)).unwrap()).sqrt();
+                        column[row] = column[row] + amplitude * T::from(sign).unwrap() * ratio;
+                    }
+                }
+            }
+
+            // On-site interaction & energies coefficient (spin symmetric)
+            column[col] = column[col] + self.diagonal_term(current_state);
+            elems.append(&mut column);
+        }
+        elems
+    }
+
+    /// Outputs the eigenvalues of the block of even or odd states of a S_z = 0
+    /// sector under the spin inversion using LAPACK 'sspevd' (or 'dspevd').
+    /// Panics if magnetic fields break the symmetry.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let model = Hubbard::new(Lattice::chain(6), 1., 4.);
+    /// let basis = SpinInversionBasis::new(Sector::new(6, 3, 3), -1);
+    /// let (info, eigen_vals) = model.get_spin_inversion_eigenvalues(&basis);
+    /// ```
+    pub fn get_spin_inversion_eigenvalues(&self, basis: &SpinInversionBasis) -> (i32, Vec<T>) {
+        assert!(
+            self.h.is_zero() && self.lattice.site_fields.iter().all(|&h_i| h_i == 0.),
+            "Magnetic fields break the spin inversion"
+        );
+        lapack_diagonalization(self.build_spin_inversion_block(basis))
+    }
+
+    /// Outputs the eigenvalues and the requested eigenvectors of the block
+    /// associated with the sector containing 'n_up' spin up and 'n_down' spin
+    /// down fermions, along with the sector basis. Only the eigenvalues are
+    /// available for twisted boundaries.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let model = Hubbard::new(Lattice::chain(6), 1., 2.);
+    /// let pairs = model.get_sector_eigenpairs(3, 3, Eigenvectors::Lowest(1));
+    /// println!("Ground state: {:?}", pairs.eigen_vects[0]);
+    /// ```
+    pub fn get_sector_eigenpairs(
+        &self,
+        n_up: u32,
+        n_down: u32,
+        vectors: Eigenvectors,
+    ) -> BlockEigenpairs<T> {
+        let sector: Sector = Sector::new(self.lattice.n_sites, n_up, n_down);
+        if !self.lattice.is_real() {
+            assert_eq!(
+                Eigenvectors::Skip,
+                vectors,
+                "Eigenvectors of twisted boundaries are not available"
+            );
+            let (info, eigen_vals) = self.complex_sector_eigenvalues(&sector);
+            return BlockEigenpairs {
+                sector,
+                states: sector.basis(),
+                info,
+                eigen_vals,
+                eigen_vects: Vec::new(),
+            };
+        }
+        let (sub_states, matrix_elems) = self.build_sector_block(&sector);
+        let (info, eigen_vals, eigen_vects) = lapack_eigenpairs(matrix_elems, vectors);
+        BlockEigenpairs {
+            sector,
+            states: sub_states,
+            info,
+            eigen_vals,
+            eigen_vects,
+        }
+    }
+
+    /// Applies the block of the hamiltonian associated with given sector to a
+    /// vector expressed in the sector basis, without storing the block.
+    ///
+    /// Each component of the output is computed independently (in parallel)
+    /// using the kinetic and interaction terms of the corresponding Fock state.
+    pub fn apply_hamiltonian(&self, sector: &Sector, vector: &[T], output: &mut [T]) {
+        output.par_iter_mut().enumerate().for_each(|(row, out)| {
+            // On-site interaction & energies coefficient
+            let state: State = sector.state(row);
+            let mut coefficient: T = self.diagonal_term(state) * vector[row];
+
+            // Kinetic terms
+            for (linked_state, amplitude) in self.kinetic_term(state) {
+                coefficient = coefficient + amplitude * vector[sector.index(linked_state)];
+            }
+            *out = coefficient;
+        });
+    }
+
+    /// Outputs the lowest eigenpairs of the sector containing 'n_up' spin up
+    /// and 'n_down' spin down fermions using a matrix-free Lanczos solver.
+    ///
+    /// The 'info' attribute of the output is 0 if the eigenpairs converged
+    /// and 1 if the maximum number of iterations was reached.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let model = Hubbard::new(Lattice::chain(14), 1., 4.);
+    /// let params = Lanczos { n_eigen: 2, reorthogonalize: true, ..Lanczos::default() };
+    /// let pairs = model.get_sector_lanczos(7, 7, &params);
+    /// println!("Ground state energy at half filling: {}", pairs.eigen_vals[0]);
+    /// ```
+    pub fn get_sector_lanczos(
+        &self,
+        n_up: u32,
+        n_down: u32,
+        params: &Lanczos,
+    ) -> BlockEigenpairs<T> {
+        let sector: Sector = Sector::new(self.lattice.n_sites, n_up, n_down);
+        let (converged, eigen_vals, eigen_vects) = lanczos_eigenpairs(
+            sector.dimension(),
+            |vector: &[T], output: &mut [T]| self.apply_hamiltonian(&sector, vector, output),
+            params,
+        );
+        BlockEigenpairs {
+            sector,
+            states: sector.basis(),
+            info: if converged { 0 } else { 1 },
+            eigen_vals,
+            eigen_vects,
+        }
+    }
+
     /// Outputs the eigenvalues of Hubbard hamiltonian by diagonalizing all
-    /// of it's blocks using LAPACK 'sspevd' Fortran implementation.
+    /// of it's blocks inside given sectors using LAPACK 'sspevd' (or 'dspevd')
+    /// Fortran implementation.
+    ///
+    /// The blocks are first discovered from the states of each sector and are
+    /// then built and diagonalized concurrently using 'n_threads' threads (0
+    /// uses all available cores), as complex hermitian blocks for twisted
+    /// boundaries (eigenvalues only). The eigenvalues are saved and stored inside
+    /// 'data_dir/eigen_values.csv', labelled by the (N_up, N_down) sector of
+    /// their block, in the order the blocks were discovered. The requested
+    /// eigenvectors of each block are stored inside 'data_dir/eigen_vectors.csv'.
     ///
-    /// The eigenvalues are saved and stored inside './Data/eigen_vals.csv'.
-    pub fn get_eigenvalues(&self) {
+    /// With 'particle_hole_mapping' (eigenvalues only, see
+    /// 'is_particle_hole_symmetric'), the sectors with N > n_sites are not
+    /// diagonalized: their eigenvalues are those of their particle-hole image
+    /// (N < n_sites) shifted by 'particle_hole_shift', which halves the work of
+    /// a full spectrum.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let model = Hubbard::new(Lattice::chain(6), 1., 2.);
+    /// model.get_eigenvalues(&Sector::all(6), 0, Eigenvectors::Skip, false, "./Data");
+    /// ```
+    pub fn get_eigenvalues(
+        &self,
+        sectors: &[Sector],
+        n_threads: usize,
+        vectors: Eigenvectors,
+        particle_hole_mapping: bool,
+        data_dir: &str,
+    ) {
+        let n_sites: u32 = self.lattice.n_sites;
+        if particle_hole_mapping {
+            assert_eq!(
+                Eigenvectors::Skip,
+                vectors,
+                "Particle-hole mapping only gives the eigenvalues"
+            );
+            assert!(
+                self.is_particle_hole_symmetric(),
+                "Particle-hole mapping needs a particle-hole symmetric model"
+            );
+        }
+
+        // Sectors actually diagonalized (images of the sectors with N > n_sites)
+        let source_of = |sector: &Sector| -> Sector {
+            if particle_hole_mapping && sector.n_up + sector.n_down > n_sites {
+                sector.particle_hole_image()
+            } else {
+                *sector
+            }
+        };
+        let mut sources: Vec<Sector> = Vec::new();
+        for sector in sectors.iter() {
+            let source: Sector = source_of(sector);
+            if !sources.contains(&source) {
+                sources.push(source);
+            }
+        }
+
         // Data file initialization (csv)
-        let data_path: String = String::from("./Data/eigen_values.csv");
+        let data_path: String = format!("{}/eigen_values.csv", data_dir);
         let mut eig_wtr: csv::Writer<std::fs::File> = init_file_writter(&data_path, false);
+        write_eigenvalues_header(&mut eig_wtr).unwrap();
+
+        // Eigenvectors file initialization (csv) if requested
+        let mut vec_wtr: Option<csv::Writer<std::fs::File>> = match vectors {
+            Eigenvectors::Skip => None,
+            _ => {
+                let vectors_path: String = format!("{}/eigen_vectors.csv", data_dir);
+                let mut wtr = init_file_writter(&vectors_path, false);
+                wtr.write_record(EIGENVECTORS_HEADER).unwrap();
+                Some(wtr)
+            }
+        };
+
+        // Main loop over the sectors (blocks never span more than one sector)
+        let mut blocks: Vec<BlockBasis> = Vec::new();
+        for sector in sources.iter() {
+            // Already visited states (one bit per state of the sector)
+            let mut visited: StateBitSet = StateBitSet::new(sector.dimension());
+            for state_i in sector.basis() {
+                // Verifying if the state was already used
+                if !visited.contains(sector.index(state_i) as State) {
+                    // State bank from 'state_i'
+                    let basis: BlockBasis = self.discover_block(state_i);
+
+                    // Marking the states of the block as visited
+                    for sub_state in basis.states.iter() {
+                        visited.insert(sector.index(*sub_state) as State);
+                    }
+                    blocks.push(basis);
+                }
+            }
+        }
 
         // Progress bar
-        let pb = init_progress_bar(
-            String::from("Diagonalization"),
-            (4 as u64).pow(self.n_sites),
-        );
+        let pb = init_progress_bar(String::from("Diagonalization"), blocks.len() as u64);
 
-        // Vector containing the blocs of the matrix & already visited states
-        let mut visited: Vec<i32> = Vec::new();
-        let mut blocks: Vec<Vec<i32>> = Vec::new();
-
-        // Main loop over Fock space states (4^(n_sites))
-        for state_i in 0..(4 as i32).pow(self.n_sites) {
-            // Verifying if the state was already used
-            if !visited.contains(&state_i) {
-                // State bank from 'state_i;
-                let (sub_block, matrix_elems) = self.find_sub_block(state_i);
-                let (_success, eigen_vals): (i32, Vec<f32>) = lapack_diagonalization(matrix_elems);
-
-                // Write eigenvalues to text file
-                eig_wtr.serialize(eigen_vals).unwrap();
-
-                // Building already visited states list
-                let mut filtered: Vec<i32> = sub_block.clone();
-                filtered.retain(|i: &i32| !visited.contains(i));
-                visited.append(&mut filtered);
-                blocks.push(sub_block);
-                pb.inc(1)
+        // Building & diagonalizing blocks concurrently (ordering is preserved)
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(n_threads)
+            .build()
+            .unwrap();
+        let real: bool = self.lattice.is_real();
+        let spectra: Vec<(i32, Vec<T>, Vec<Vec<T>>)> = pool.install(|| {
+            blocks
+                .par_iter()
+                .map(|basis| {
+                    let spectrum = if real {
+                        let matrix_elems: Vec<T> =
+                            self.block_elements(&basis.states, |s| basis.index_of(s));
+                        lapack_eigenpairs(matrix_elems, vectors)
+                    } else {
+                        let matrix_elems: Vec<c64> =
+                            self.complex_block_elements(&basis.states, |s| basis.index_of(s));
+                        let (info, eigen_vals) = lapack_hermitian_diagonalization(matrix_elems);
+                        let eigen_vals: Vec<T> = eigen_vals
+                            .into_iter()
+                            .map(|e| T::from(e).unwrap())
+                            .collect();
+                        (info, eigen_vals, Vec::new())
+                    };
+                    pb.inc(1);
+                    spectrum
+                })
+                .collect()
+        });
+        pb.finish();
+
+        // Write eigenvalues (and eigenvectors) to text file with their quantum
+        // numbers, the images of the sectors with N > n_sites being shifted
+        let block_sectors: Vec<Sector> = blocks
+            .iter()
+            .map(|basis| Sector::of_state(n_sites, basis.states[0]))
+            .collect();
+        for sector in sectors.iter() {
+            let source: Sector = source_of(sector);
+            let shift: T = if source == *sector {
+                T::zero()
             } else {
-                pb.inc(1)
+                self.particle_hole_shift(source.state(0))
+            };
+            for ((basis, (info, eigen_vals, eigen_vects)), block_sector) in
+                blocks.iter().zip(spectra.iter()).zip(block_sectors.iter())
+            {
+                if *block_sector != source {
+                    continue;
+                }
+                let eigen_vals: Vec<T> = eigen_vals.iter().map(|&e| e + shift).collect();
+                write_sector_eigenvalues(&mut eig_wtr, sector, basis.len(), *info, &eigen_vals)
+                    .unwrap();
+                if let Some(wtr) = vec_wtr.as_mut() {
+                    write_sector_eigenvectors(wtr, sector, &basis.states, eigen_vects).unwrap();
+                }
             }
         }
     }
@@ -329,8 +1264,26 @@ impl Hubbard {
 mod tests {
 
     use std::assert_eq;
+    use std::f64::consts::PI;
 
-    use crate::fock_space::{FockState, Hubbard};
+    use crate::array_utils::{Eigenvectors, Scalar};
+    use crate::basis::{BlockBasis, State};
+    use crate::fock_space::{
+        eta_squared_term, spin_squared_term, BlockEigenpairs, FockState, Hubbard,
+    };
+    use crate::lanczos::Lanczos;
+    use crate::lattice::{Bond, Boundary, Lattice};
+    use crate::momentum::MomentumBasis;
+    use crate::sectors::Sector;
+    use crate::spin_inversion::SpinInversionBasis;
+    use crate::symmetry::{PointGroup, Symmetry, SymmetryBasis};
+
+    // Eigenvalues (and LAPACK exit code) of the block of given sector
+    fn sector_eigenvalues<T: Scalar>(model: &Hubbard<T>, n_up: u32, n_down: u32) -> (i32, Vec<T>) {
+        let pairs: BlockEigenpairs<T> =
+            model.get_sector_eigenpairs(n_up, n_down, Eigenvectors::Skip);
+        (pairs.info, pairs.eigen_vals)
+    }
 
     #[test]
     fn test_fock_scalar() {
@@ -366,7 +1319,7 @@ mod tests {
 
         // Testing invalid fermion creation
         test_state.create(0);
-        assert_eq!(true, test_state.is_null);
+        assert!(test_state.is_null);
     }
 
     #[test]
@@ -381,11 +1334,39 @@ mod tests {
         // Testing for valid fermion anihilation
         // Answer should be: |0 0 ; 1 1>
         test_state.destroy(1);
-        assert_eq!(-3, test_state.integer);
+        assert_eq!(3, test_state.integer);
+        assert_eq!(-1, test_state.sign);
 
         // Testing for invalid fermion anihilation
         test_state.destroy(1);
-        assert_eq!(true, test_state.is_null);
+        assert!(test_state.is_null);
+    }
+
+    #[test]
+    fn test_fock_jordan_wigner() {
+        // Test Fock state has form: |1 1 0 ; 0 0 0>
+        let mut test_state: FockState = FockState {
+            n_sites: 3,
+            integer: 48,
+            is_null: false,
+            sign: 1,
+        };
+        // No fermion before orbital 0
+        // Answer should be: |0 1 0 ; 0 0 0>
+        test_state.destroy(0);
+        assert_eq!(16, test_state.integer);
+
+        // One fermion (orbital 1) before orbital 2
+        // Answer should be: -|0 1 1 ; 0 0 0>
+        test_state.create(2);
+        assert_eq!(24, test_state.integer);
+        assert_eq!(-1, test_state.sign);
+
+        // Two fermions before orbital 3 (first spin down orbital)
+        // Answer should be: -|0 1 1 ; 1 0 0>
+        test_state.create(3);
+        assert_eq!(28, test_state.integer);
+        assert_eq!(-1, test_state.sign);
     }
 
     #[test]
@@ -404,17 +1385,34 @@ mod tests {
         // Testing for non-existing fermion
         test_state.destroy(0);
         test_state.number(0);
-        assert_eq!(true, test_state.is_null);
+        assert!(test_state.is_null);
+    }
+
+    #[test]
+    fn test_spin_squared() {
+        // | 1 0 ; 0 1 > -> | 1 0 ; 0 1 > - | 0 1 ; 1 0 >, the singlet
+        // | 1 0 ; 0 1 > + | 0 1 ; 1 0 > being annihilated
+        assert_eq!(vec![(6, -1.), (9, 1.)], spin_squared_term(9, 2));
+        assert_eq!(vec![(6, 1.), (9, -1.)], spin_squared_term(6, 2));
+        // Doubly occupied site (singlet) and fully polarized triplet
+        assert!(spin_squared_term(10, 2).is_empty());
+        assert_eq!(vec![(12, 2.)], spin_squared_term(12, 2));
+    }
+
+    #[test]
+    fn test_eta_squared() {
+        // Empty and doubly occupied site: eta = 1/2, singly occupied: eta = 0
+        assert_eq!(vec![(0, 0.75)], eta_squared_term(0, 1, &[1]));
+        assert_eq!(vec![(3, 0.75)], eta_squared_term(3, 1, &[1]));
+        assert!(eta_squared_term(2, 1, &[1]).is_empty());
+        // Pair hopping between the sublattices of two sites: | 1 0 ; 1 0 > -> - | 0 1 ; 0 1 >
+        assert_eq!(vec![(5, -1.), (10, 1.)], eta_squared_term(10, 2, &[1, -1]));
     }
 
     #[test]
     fn test_hubbard_interaction() {
         // Test hubbard instance
-        let test_model: Hubbard = Hubbard {
-            n_sites: 2,
-            t: 1.,
-            u: 2.,
-        };
+        let test_model: Hubbard = Hubbard::new(Lattice::dimer(), 1., 2.);
         assert_eq!(4., test_model.interaction_term(15));
         assert_eq!(2., test_model.interaction_term(5));
         assert_eq!(0., test_model.interaction_term(1));
@@ -423,38 +1421,516 @@ mod tests {
     #[test]
     fn test_hubbard_hoppings() {
         // Test hubbard instance
-        let test_model: Hubbard = Hubbard {
-            n_sites: 2,
-            t: 1.,
-            u: 2.,
-        };
-        let empty: Vec<i32> = Vec::new();
+        let test_model: Hubbard = Hubbard::new(Lattice::dimer(), 1., 2.);
+        let empty: Vec<(State, f64)> = Vec::new();
         assert_eq!(empty, test_model.kinetic_term(0));
-        assert_eq!(vec![2], test_model.kinetic_term(1));
-        assert_eq!(vec![6, 9], test_model.kinetic_term(5));
+        assert_eq!(vec![(2, 1.)], test_model.kinetic_term(1));
+        assert_eq!(vec![(6, 1.), (9, 1.)], test_model.kinetic_term(5));
     }
 
     #[test]
-    fn test_hubbard_blocks() {
-        // Test hubbard instance
-        let test_model: Hubbard = Hubbard {
-            n_sites: 3,
-            t: 1.,
-            u: 2.,
+    fn test_hubbard_bond_list() {
+        // Three sites molecule with one stronger bond and site parameters
+        let bonds: Vec<Bond> = vec![
+            Bond {
+                sites: (0, 1),
+                hopping: 1.,
+                repulsion: 1.,
+                winding: (0, 0),
+            },
+            Bond {
+                sites: (1, 2),
+                hopping: 2.,
+                repulsion: 1.,
+                winding: (0, 0),
+            },
+        ];
+        let mut lattice: Lattice = Lattice::from_bonds(3, bonds);
+        lattice.site_energies[2] = -1.;
+        lattice.site_interactions[0] = 0.5;
+        let test_model: Hubbard = Hubbard::new(lattice, 1., 4.);
+
+        // | 0 1 0 ; 0 0 0 > hops to sites 0 and 2
+        assert_eq!(vec![(8, 2.), (32, 1.)], test_model.kinetic_term(16));
+        // | 1 0 1 ; 1 0 1 > has interactions 2 + 4 and energies -2
+        assert_eq!(4., test_model.diagonal_term(45));
+    }
+
+    #[test]
+    fn test_hubbard_long_range_sign() {
+        // Triangle molecule (bond (0, 2) is not between neighbouring orbitals)
+        let bonds: Vec<Bond> = [(0, 1), (1, 2), (0, 2)]
+            .iter()
+            .map(|&sites| Bond {
+                sites,
+                hopping: 1.,
+                repulsion: 0.,
+                winding: (0, 0),
+            })
+            .collect();
+        let test_model: Hubbard = Hubbard::new(Lattice::from_bonds(3, bonds), 1., 0.);
+
+        // | 1 1 0 ; 0 0 0 > hops to | 0 1 1 ; 0 0 0 > across an occupied orbital
+        assert_eq!(vec![(24, -1.), (40, 1.)], test_model.kinetic_term(48));
+    }
+
+    #[test]
+    fn test_hubbard_next_neighbours() {
+        // Single fermion on the t-t'-t'' ring follows the band structure
+        // 2 t cos(k) + 2 t' cos(2k) + 2 t'' cos(3k)
+        let mut lattice: Lattice = Lattice::chain(6);
+        lattice.set_shell_hopping(2, 0.5);
+        lattice.set_shell_hopping(3, 0.25);
+        let test_model: Hubbard = Hubbard::new(lattice, 1., 4.);
+        let (info, eigen_vals) = sector_eigenvalues(&test_model, 1, 0);
+        let mut band: Vec<f64> = (0..6)
+            .map(|m| std::f64::consts::PI * m as f64 / 3.)
+            .map(|k| 2. * k.cos() + (2. * k).cos() + 0.5 * (3. * k).cos())
+            .collect();
+        band.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        assert_eq!(0, info);
+        for (e, b) in eigen_vals.iter().zip(band.iter()) {
+            assert!((e - b).abs() < 1e-12);
+        }
+
+        // | 1 1 0 0 0 0 ; 0 > hops across site 1 with a fermion sign
+        assert!(test_model.kinetic_term(3 << 10).contains(&(3 << 9, -0.5)));
+    }
+
+    #[test]
+    fn test_hubbard_boundaries() {
+        // Single fermion on the ring of 5 sites follows 2 t cos(k), with
+        // k = (2 pi m + theta) / 5 for twisted boundaries
+        let single_particle = |boundary: Boundary| -> Vec<f64> {
+            let mut lattice: Lattice = Lattice::chain(5);
+            lattice.boundaries = [boundary; 2];
+            let test_model: Hubbard = Hubbard::new(lattice, 1., 4.);
+            let (info, eigen_vals) = sector_eigenvalues(&test_model, 1, 0);
+            assert_eq!(0, info);
+            eigen_vals
+        };
+        let band = |momenta: Vec<f64>| -> Vec<f64> {
+            let mut energies: Vec<f64> = momenta.iter().map(|k| 2. * k.cos()).collect();
+            energies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            energies
+        };
+        let close = |left: Vec<f64>, right: Vec<f64>| {
+            for (l, r) in left.iter().zip(right.iter()) {
+                assert!((l - r).abs() < 1e-10);
+            }
+        };
+        for theta in [PI, 0.3 * PI] {
+            let momenta: Vec<f64> = (0..5).map(|m| (2. * PI * m as f64 + theta) / 5.).collect();
+            close(band(momenta), single_particle(Boundary::Twisted(theta)));
+        }
+        let momenta: Vec<f64> = (0..5).map(|m| (2. * m as f64 + 1.) * PI / 5.).collect();
+        close(band(momenta), single_particle(Boundary::Antiperiodic));
+        let momenta: Vec<f64> = (1..6).map(|m| PI * m as f64 / 6.).collect();
+        close(band(momenta), single_particle(Boundary::Open));
+
+        // Complex blocks of the pi twist match the real antiperiodic blocks
+        let mut lattice: Lattice = Lattice::chain(4);
+        lattice.boundaries = [Boundary::Antiperiodic; 2];
+        let real_model: Hubbard = Hubbard::new(lattice.clone(), 1., 4.);
+        lattice.boundaries = [Boundary::Twisted(PI); 2];
+        let complex_model: Hubbard = Hubbard::new(lattice, 1., 4.);
+        close(
+            sector_eigenvalues(&real_model, 2, 2).1,
+            complex_model
+                .complex_sector_eigenvalues(&Sector::new(4, 2, 2))
+                .1,
+        );
+    }
+
+    #[test]
+    fn test_hubbard_momentum_sectors() {
+        // Single fermion of momentum k on the ring of 5 sites has energy 2 t cos(k)
+        let test_model: Hubbard = Hubbard::new(Lattice::chain(5), 1., 4.);
+        for m in 0..5 {
+            let basis: MomentumBasis = MomentumBasis::new(Sector::new(5, 1, 0), m);
+            let (info, eigen_vals) = test_model.get_momentum_eigenvalues(&basis);
+            assert_eq!(0, info);
+            assert!((eigen_vals[0] - 2. * basis.k().cos()).abs() < 1e-10);
+        }
+
+        // Momentum blocks split the spectrum of the sector (with longer range
+        // hoppings and extended repulsion)
+        let mut lattice: Lattice = Lattice::chain(6);
+        lattice.set_shell_hopping(2, -0.3);
+        let mut test_model: Hubbard = Hubbard::new(lattice, 1., 4.);
+        test_model.v = 0.5;
+        for (n_up, n_down) in [(3, 3), (2, 1), (3, 0)] {
+            let sector: Sector = Sector::new(6, n_up, n_down);
+            let mut energies: Vec<f64> = (0..6)
+                .flat_map(|m| {
+                    let basis: MomentumBasis = MomentumBasis::new(sector, m);
+                    test_model.get_momentum_eigenvalues(&basis).1
+                })
+                .collect();
+            energies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let (_, eigen_vals) = sector_eigenvalues(&test_model, n_up, n_down);
+            for (e_k, e) in energies.iter().zip(eigen_vals.iter()) {
+                assert!((e_k - e).abs() < 1e-10);
+            }
+        }
+    }
+
+    #[test]
+    fn test_hubbard_point_group() {
+        // Irreducible representations split the spectrum of the sector (levels
+        // of E are twice degenerate)
+        let close_spectra = |model: &Hubbard, group: PointGroup, n_up: u32, n_down: u32| {
+            let symmetry: Symmetry = Symmetry::new(group, &model.lattice).unwrap();
+            let sector: Sector = Sector::new(model.lattice.n_sites, n_up, n_down);
+            let mut energies: Vec<f64> = Vec::new();
+            for (irrep, representation) in symmetry.irreps.iter().enumerate() {
+                let basis: SymmetryBasis = SymmetryBasis::new(&symmetry, sector, irrep);
+                let (info, eigen_vals) = model.get_symmetry_eigenvalues(&basis);
+                assert_eq!(0, info);
+                for _ in 0..representation.dimension {
+                    energies.extend(eigen_vals.iter());
+                }
+            }
+            energies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+            let (_, eigen_vals) = sector_eigenvalues(model, n_up, n_down);
+            assert_eq!(eigen_vals.len(), energies.len());
+            for (e_g, e) in energies.iter().zip(eigen_vals.iter()) {
+                assert!((e_g - e).abs() < 1e-10);
+            }
+        };
+        let mut open: Lattice = Lattice::chain(5);
+        open.boundaries = [Boundary::Open; 2];
+        close_spectra(&Hubbard::new(open, 1., 4.), PointGroup::Reflection, 2, 1);
+        let ring: Hubbard = Hubbard::new(Lattice::chain(6), 1., 4.);
+        close_spectra(&ring, PointGroup::Reflection, 3, 0);
+        let plaquette: Hubbard = Hubbard::new(Lattice::square(2, 2), 1., 4.);
+        close_spectra(&plaquette, PointGroup::C4v, 2, 1);
+        let square: Hubbard = Hubbard::new(Lattice::square(3, 3), 1., 4.);
+        close_spectra(&square, PointGroup::C4v, 2, 0);
+
+        // Plaquette at half filling is d-wave (B1) with respect to its two
+        // holes ground state (s-wave, A1)
+        let test_model: Hubbard = Hubbard::new(Lattice::square(2, 2), 1., 4.);
+        let symmetry: Symmetry = Symmetry::new(PointGroup::C4v, &test_model.lattice).unwrap();
+        for (n_up, irrep) in [(2, "B1"), (1, "A1")] {
+            let sector: Sector = Sector::new(4, n_up, n_up);
+            let (ground_irrep, _) = (0..symmetry.irreps.len())
+                .filter_map(|irrep| {
+                    let basis: SymmetryBasis = SymmetryBasis::new(&symmetry, sector, irrep);
+                    let (_, eigen_vals) = test_model.get_symmetry_eigenvalues(&basis);
+                    eigen_vals
+                        .first()
+                        .map(|&energy| (symmetry.irreps[irrep].name, energy))
+                })
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .unwrap();
+            assert_eq!(irrep, ground_irrep);
+        }
+    }
+
+    #[test]
+    fn test_hubbard_spin_inversion() {
+        // Even and odd blocks split the spectrum of the sector
+        let mut lattice: Lattice = Lattice::square(2, 3);
+        lattice.site_energies[1] = 0.5;
+        let mut test_model: Hubbard = Hubbard::new(lattice, 1., 4.);
+        test_model.v = 0.5;
+        let sector: Sector = Sector::new(6, 2, 2);
+        let mut energies: Vec<f64> = [1, -1]
+            .iter()
+            .flat_map(|&parity| {
+                let basis: SpinInversionBasis = SpinInversionBasis::new(sector, parity);
+                test_model.get_spin_inversion_eigenvalues(&basis).1
+            })
+            .collect();
+        energies.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        let (_, eigen_vals) = sector_eigenvalues(&test_model, 2, 2);
+        for (e_z, e) in energies.iter().zip(eigen_vals.iter()) {
+            assert!((e_z - e).abs() < 1e-10);
+        }
+
+        // Two sites at U = 0: singlet ground state (even) and triplet at 0 (odd)
+        let test_model: Hubbard = Hubbard::new(Lattice::dimer(), 1., 0.);
+        let sector: Sector = Sector::new(2, 1, 1);
+        let even: SpinInversionBasis = SpinInversionBasis::new(sector, 1);
+        let odd: SpinInversionBasis = SpinInversionBasis::new(sector, -1);
+        let (_, even_vals) = test_model.get_spin_inversion_eigenvalues(&even);
+        let (_, odd_vals) = test_model.get_spin_inversion_eigenvalues(&odd);
+        for (e, expected) in even_vals.iter().zip([-2., 0., 2.]) {
+            assert!((e - expected).abs() < 1e-10);
+        }
+        assert_eq!(1, odd_vals.len());
+        assert!(odd_vals[0].abs() < 1e-10);
+
+        // Empty sector: the vacuum is even and the odd block has no states
+        let vacuum: Sector = Sector::new(2, 0, 0);
+        let odd: SpinInversionBasis = SpinInversionBasis::new(vacuum, -1);
+        assert_eq!(0, odd.dimension());
+        assert_eq!((0, vec![]), test_model.get_spin_inversion_eigenvalues(&odd));
+        let even: SpinInversionBasis = SpinInversionBasis::new(vacuum, 1);
+        assert_eq!(
+            (0, vec![0.]),
+            test_model.get_spin_inversion_eigenvalues(&even)
+        );
+    }
+
+    #[test]
+    fn test_hubbard_eta_pairing_symmetry() {
+        // Bipartite ring with uniform parameters (and a magnetic field)
+        let mut test_model: Hubbard = Hubbard::new(Lattice::chain(4), 1., 4.);
+        test_model.h = 0.5;
+        assert!(test_model.is_eta_pairing_symmetric());
+
+        // Extended repulsion, local energies or interactions, odd ring
+        test_model.v = 1.;
+        assert!(!test_model.is_eta_pairing_symmetric());
+        let mut lattice: Lattice = Lattice::chain(4);
+        lattice.site_energies[0] = 0.5;
+        assert!(!Hubbard::new(lattice, 1., 4.).is_eta_pairing_symmetric());
+        let mut lattice: Lattice = Lattice::chain(4);
+        lattice.site_interactions[1] = 2.;
+        assert!(!Hubbard::new(lattice, 1., 4.).is_eta_pairing_symmetric());
+        assert!(!Hubbard::new(Lattice::chain(3), 1., 4.).is_eta_pairing_symmetric());
+    }
+
+    #[test]
+    fn test_hubbard_particle_hole_mapping() {
+        // Bipartite ring with chemical potential and uniform repulsions
+        let mut test_model: Hubbard = Hubbard::new(Lattice::chain(4), 1., 4.);
+        test_model.mu = 0.3;
+        test_model.v = 0.5;
+        assert!(test_model.is_particle_hole_symmetric());
+        for (n_up, n_down) in [(3, 2), (4, 1), (4, 4)] {
+            let sector: Sector = Sector::new(4, n_up, n_down);
+            let image: Sector = sector.particle_hole_image();
+            let shift: f64 = test_model.particle_hole_shift(image.state(0));
+            let (_, eigen_vals) = sector_eigenvalues(&test_model, n_up, n_down);
+            let (_, image_vals) = sector_eigenvalues(&test_model, image.n_up, image.n_down);
+            for (e, e_image) in eigen_vals.iter().zip(image_vals.iter()) {
+                assert!((e - e_image - shift).abs() < 1e-10);
+            }
+        }
+
+        // Full spectrum written with and without the mapping
+        let dir = std::env::temp_dir().join("hubbies_test_particle_hole");
+        let energies = |mapping: bool| -> Vec<f64> {
+            let data_dir: String = format!("{}/{}", dir.display(), mapping);
+            std::fs::create_dir_all(&data_dir).unwrap();
+            test_model.get_eigenvalues(&Sector::all(4), 1, Eigenvectors::Skip, mapping, &data_dir);
+            let mut reader = csv::ReaderBuilder::new()
+                .delimiter(b' ')
+                .from_path(format!("{}/eigen_values.csv", data_dir))
+                .unwrap();
+            reader
+                .records()
+                .map(|record| record.unwrap()[5].parse::<f64>().unwrap())
+                .collect()
         };
-        let sub_states: Vec<i32> = vec![9, 10, 12, 17, 18, 20, 33, 34, 36];
+        let (direct, mapped) = (energies(false), energies(true));
+        assert_eq!(direct.len(), mapped.len());
+        for (e, e_mapped) in direct.iter().zip(mapped.iter()) {
+            assert!((e - e_mapped).abs() < 1e-10);
+        }
+
+        // Fields, frustration and inhomogeneous interactions break the mapping
+        test_model.h = 0.5;
+        assert!(!test_model.is_particle_hole_symmetric());
+        let mut lattice: Lattice = Lattice::chain(4);
+        lattice.site_interactions[0] = 2.;
+        assert!(!Hubbard::new(lattice.clone(), 1., 4.).is_particle_hole_symmetric());
+        let mut symmetric: Hubbard = Hubbard::new(lattice, 1., 4.);
+        symmetric.particle_hole = true;
+        assert!(symmetric.is_particle_hole_symmetric());
+        assert!(!Hubbard::new(Lattice::chain(5), 1., 4.).is_particle_hole_symmetric());
+    }
+
+    #[test]
+    fn test_hubbard_large_cluster() {
+        // Ring of 20 sites needs 40 bits Fock states
+        let test_model: Hubbard = Hubbard::new(Lattice::chain(20), 1., 4.);
+        let state: State = 1 << 39;
+        assert_eq!(
+            vec![(1 << 20, 1.), (1 << 38, 1.)],
+            test_model.kinetic_term(state)
+        );
+        assert_eq!(Sector::new(20, 1, 0), Sector::of_state(20, state));
+    }
+
+    #[test]
+    fn test_hubbard_chemical_potential() {
+        // Dimer in the particle-hole symmetric form with chemical potential
+        let mut test_model: Hubbard = Hubbard::new(Lattice::dimer(), 1., 4.);
+        test_model.particle_hole = true;
+        assert_eq!((0, vec![2.]), sector_eigenvalues(&test_model, 0, 0));
+        assert_eq!((0, vec![2.]), sector_eigenvalues(&test_model, 2, 2));
+
+        // Half filling ground state is shifted by -U N / 2 + U n_sites / 4
+        let (_, eigen_vals): (i32, Vec<f64>) = sector_eigenvalues(&test_model, 1, 1);
+        assert!((eigen_vals[0] - (4. - 32_f64.sqrt()) / 2. + 2.).abs() < 1e-12);
+
+        // Chemical potential shifts each sector by -mu N
+        test_model.mu = 1.5;
+        assert_eq!(-3., test_model.chemical_shift(&Sector::new(2, 1, 1)));
+        assert_eq!((0, vec![-4.]), sector_eigenvalues(&test_model, 2, 2));
+    }
+
+    #[test]
+    fn test_hubbard_zeeman_field() {
+        // Dimer with a uniform field and a local field on site 0
+        let mut lattice: Lattice = Lattice::dimer();
+        lattice.site_fields[0] = 0.5;
+        let mut test_model: Hubbard = Hubbard::new(lattice, 1., 4.);
+        test_model.h = 2.;
+        assert_eq!(-4., test_model.zeeman_shift(&Sector::new(2, 2, 0)));
+
+        // | 1 0 ; 0 1 > feels the local field only (N_up = N_down)
+        assert_eq!(-0.5, test_model.field_term(9));
+        assert_eq!(0.5, test_model.field_term(6));
+
+        // Fully polarized sectors (no hoppings to other states)
+        assert_eq!((0, vec![-4.5]), sector_eigenvalues(&test_model, 2, 0));
+        assert_eq!((0, vec![4.5]), sector_eigenvalues(&test_model, 0, 2));
+    }
+
+    #[test]
+    fn test_hubbard_extended_interaction() {
+        // Ring of 4 sites with nearest-neighbour repulsion V = 1
+        let mut test_model: Hubbard = Hubbard::new(Lattice::chain(4), 1., 4.);
+        test_model.v = 1.;
+
+        // | 1 0 1 0 ; 1 0 1 0 > (charge density wave) has no repulsion
+        assert_eq!(0., test_model.extended_term(170));
+        assert_eq!(8., test_model.diagonal_term(170));
+        // | 1 1 0 0 ; 0 1 1 0 > has n_0 n_1 + n_1 n_2 = 2 + 2
+        assert_eq!(4., test_model.extended_term(198));
+
+        // Particle-hole form vanishes at uniform half filling
+        test_model.particle_hole = true;
+        assert_eq!(0., test_model.extended_term(195));
+        assert_eq!(-4., test_model.extended_term(170));
+
+        // | 1 0 0 1 ; 1 0 0 1 > only interacts across the boundary, which is
+        // cut along with its hopping on the open chain
+        let mut lattice: Lattice = Lattice::chain(4);
+        test_model = Hubbard::new(lattice.clone(), 1., 4.);
+        test_model.v = 1.;
+        assert_eq!(4., test_model.extended_term(153));
+        lattice.boundaries = [Boundary::Open; 2];
+        test_model = Hubbard::new(lattice, 1., 4.);
+        test_model.v = 1.;
+        assert_eq!(0., test_model.extended_term(153));
+        assert_eq!(4., test_model.extended_term(198));
+    }
+
+    #[test]
+    fn test_hubbard_blocks() {
+        // Test hubbard instance (single precision)
+        let test_model: Hubbard<f32> = Hubbard::new(Lattice::chain(3), 1., 2.);
+        let sub_states: Vec<State> = vec![9, 10, 12, 17, 18, 20, 33, 34, 36];
         let elements: Vec<f32> = vec![
             2., 1., 0., 1., 1., 0., 1., 0., 0., 0., 0., 1., 0., 1., 2., 0., 0., 1., 1., 1., 0., 1.,
             0., 0., 1., 0., 0., 0., 0., 1., 0., 0., 1., 0., 1., 0., 0., 0., 1., 0., 0., 1., 1., 1.,
             2.,
         ];
-        let (states, elems): (Vec<i32>, Vec<f32>) = test_model.find_sub_block(9);
-        let empty: Vec<i32> = Vec::new();
+        let basis: BlockBasis = test_model.discover_block(9);
+        let elems: Vec<f32> = test_model.block_elements(&basis.states, |s| basis.index_of(s));
+        let states: Vec<State> = basis.states;
+        let empty: Vec<State> = Vec::new();
         let difference: Vec<_> = sub_states
             .into_iter()
-            .filter(|elem| !states.contains(&elem))
+            .filter(|elem| !states.contains(elem))
             .collect();
         assert_eq!(empty, difference);
         assert_eq!(elements, elems);
     }
+
+    #[test]
+    fn test_hubbard_sector_block() {
+        // Test hubbard instance (single precision)
+        let test_model: Hubbard<f32> = Hubbard::new(Lattice::chain(3), 1., 2.);
+        // Sector block must match the block found from Fock state |9>
+        let basis: BlockBasis = test_model.discover_block(9);
+        let block_elems: Vec<f32> = test_model.block_elements(&basis.states, |s| basis.index_of(s));
+        let block_states: Vec<State> = basis.states;
+        let (states, elems): (Vec<State>, Vec<f32>) =
+            test_model.build_sector_block(&Sector::new(3, 1, 1));
+        assert_eq!(block_states, states);
+        assert_eq!(block_elems, elems);
+
+        // Empty and fully occupied sectors are one dimensional
+        assert_eq!((0, vec![0.]), sector_eigenvalues(&test_model, 0, 0));
+        assert_eq!((0, vec![6.]), sector_eigenvalues(&test_model, 3, 3));
+    }
+
+    #[test]
+    fn test_hubbard_eigenpairs() {
+        // Test hubbard instance (dimer)
+        let test_model: Hubbard = Hubbard::new(Lattice::dimer(), 1., 2.);
+        // Dimer ground state energy at half filling is (U - sqrt(U^2 + 16t^2))/2
+        let pairs: BlockEigenpairs =
+            test_model.get_sector_eigenpairs(1, 1, Eigenvectors::Lowest(1));
+        let ground_energy: f64 = (2. - 20_f64.sqrt()) / 2.;
+        assert_eq!(0, pairs.info);
+        assert!((pairs.eigen_vals[0] - ground_energy).abs() < 1e-12);
+        assert_eq!(1, pairs.eigen_vects.len());
+
+        // Ground state is normalized and mapped back to the sector states
+        let norm: f64 = pairs.eigen_vects[0].iter().map(|amp| amp * amp).sum();
+        assert_eq!(vec![5, 6, 9, 10], pairs.states);
+        assert!((norm - 1.).abs() < 1e-12);
+
+        // Eigenvectors are skipped on request
+        let skipped: BlockEigenpairs = test_model.get_sector_eigenpairs(1, 1, Eigenvectors::Skip);
+        assert_eq!(pairs.eigen_vals, skipped.eigen_vals);
+        assert!(skipped.eigen_vects.is_empty());
+    }
+
+    #[test]
+    fn test_hubbard_lanczos() {
+        // Test hubbard instance
+        let test_model: Hubbard = Hubbard::new(Lattice::chain(4), 1., 4.);
+        let (_, exact): (i32, Vec<f64>) = sector_eigenvalues(&test_model, 2, 2);
+
+        // Lanczos ground state must match dense diagonalization
+        let params: Lanczos = Lanczos {
+            n_eigen: 2,
+            reorthogonalize: true,
+            ..Lanczos::default()
+        };
+        let pairs: BlockEigenpairs = test_model.get_sector_lanczos(2, 2, &params);
+        assert_eq!(0, pairs.info);
+        assert!((pairs.eigen_vals[0] - exact[0]).abs() < 1e-8);
+        assert!((pairs.eigen_vals[1] - exact[1]).abs() < 1e-8);
+        assert_eq!(36, pairs.eigen_vects[0].len());
+
+        // Without reorthogonalization
+        let pairs: BlockEigenpairs = test_model.get_sector_lanczos(2, 2, &Lanczos::default());
+        assert!((pairs.eigen_vals[0] - exact[0]).abs() < 1e-8);
+    }
+
+    #[test]
+    fn test_hubbard_square_fermion_signs() {
+        // Two spin up fermions on the 3x3 square cluster are free fermions
+        let test_model: Hubbard = Hubbard::new(Lattice::square(3, 3), 1., 4.);
+        let (_, eigen_vals): (i32, Vec<f64>) = sector_eigenvalues(&test_model, 2, 0);
+
+        // Single particle energies 2t(cos(kx) + cos(ky)) with periodic momenta
+        let k = |m: usize| 2. * std::f64::consts::PI * m as f64 / 3.;
+        let mut energies: Vec<f64> = Vec::new();
+        for mx in 0..3 {
+            for my in 0..3 {
+                energies.push(2. * (k(mx).cos() + k(my).cos()));
+            }
+        }
+        let mut pairs: Vec<f64> = Vec::new();
+        for i in 0..9 {
+            for j in i + 1..9 {
+                pairs.push(energies[i] + energies[j]);
+            }
+        }
+        pairs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+        for (exact, eigen_val) in pairs.iter().zip(eigen_vals.iter()) {
+            assert!((exact - eigen_val).abs() < 1e-10);
+        }
+    }
 }
@@ -0,0 +1,329 @@
+// This module defines a matrix-free Lanczos solver used to find the lowest
+// eigenpairs of large blocks of the hamiltonian.
+//
+// Instead of storing the O(D^2) packed block, the solver only needs a function
+// applying the hamiltonian to a vector of dimension D. The Lanczos recurrence
+//
+//          H v_j = beta_{j-1} v_{j-1} + alpha_j v_j + beta_j v_{j+1}
+//
+// builds a small tridiagonal matrix whose lowest eigenvalues converge quickly
+// to the lowest eigenvalues of the block. Without reorthogonalization only
+// a few vectors are kept in memory and the eigenvectors are rebuilt with a
+// second pass of the recurrence. With (full) reorthogonalization all the
+// Lanczos vectors are stored, which avoids spurious copies of converged
+// eigenvalues at the cost of memory.
+
+use crate::array_utils::{lapack_eigenpairs, Eigenvectors, Scalar};
+
+/// Number of Lanczos iterations between two convergence checks (each check
+/// diagonalizes the whole tridiagonal matrix).
+const CHECK_INTERVAL: usize = 10;
+
+#[derive(Debug, Clone, Copy)]
+pub struct Lanczos {
+    // Public attributes
+    pub n_eigen: usize,
+    pub max_iter: usize,
+    pub tolerance: f64,
+    pub reorthogonalize: bool,
+}
+
+impl Default for Lanczos {
+    fn default() -> Self {
+        Lanczos {
+            n_eigen: 1,
+            max_iter: 300,
+            tolerance: 1e-10,
+            reorthogonalize: false,
+        }
+    }
+}
+
+/// Scalar product between two vectors.
+fn dot<T: Scalar>(left: &[T], right: &[T]) -> T {
+    left.iter()
+        .zip(right)
+        .fold(T::zero(), |acc, (&l, &r)| acc + l * r)
+}
+
+/// Computes y = y + a * x.
+fn axpy<T: Scalar>(a: T, x: &[T], y: &mut [T]) {
+    for (y_i, &x_i) in y.iter_mut().zip(x) {
+        *y_i = *y_i + a * x_i;
+    }
+}
+
+/// Builds a normalized pseudo-random starting vector. The sequence is fixed
+/// (xorshift) so that results are reproducible from one run to another.
+fn initial_vector<T: Scalar>(dimension: usize) -> Vec<T> {
+    let mut seed: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut vector: Vec<T> = (0..dimension)
+        .map(|_| {
+            seed ^= seed << 13;
+            seed ^= seed >> 7;
+            seed ^= seed << 17;
+            T::from((seed >> 11) as f64 / (1_u64 << 53) as f64 - 0.5).unwrap()
+        })
+        .collect();
+    let norm: T = dot(&vector, &vector).sqrt();
+    vector.iter_mut().for_each(|v_i| *v_i = *v_i / norm);
+    vector
+}
+
+/// Diagonalizes the tridiagonal Lanczos matrix defined by its diagonal
+/// 'alphas' and its off-diagonal 'betas'.
+fn tridiagonal_eigenpairs<T: Scalar>(alphas: &[T], betas: &[T]) -> (Vec<T>, Vec<Vec<T>>) {
+    // Packing the upper triangle 'column-wise' as LAPACK would recommend
+    let mut elements: Vec<T> = Vec::with_capacity(alphas.len() * (alphas.len() + 1) / 2);
+    for col in 0..alphas.len() {
+        let mut column: Vec<T> = vec![T::zero(); col + 1];
+        if col > 0 {
+            column[col - 1] = betas[col - 1];
+        }
+        column[col] = alphas[col];
+        elements.append(&mut column);
+    }
+    let (_info, eigen_vals, eigen_vects) = lapack_eigenpairs(elements, Eigenvectors::All);
+    (eigen_vals, eigen_vects)
+}
+
+/// Finds the 'n_eigen' lowest eigenpairs of the hermitian operator of given
+/// dimension defined by 'apply', where apply(v, output) sets output = H v.
+///
+/// It outputs a tuple containing a convergence flag (residuals below the
+/// tolerance relative to the largest Ritz value), the lowest eigenvalues and
+/// their (normalized) eigenvectors. If the recurrence stops on an invariant
+/// subspace holding fewer than 'n_eigen' levels, only these levels are given
+/// and the run is reported as not converged.
+///
+/// Examples
+///
+/// ```rust
+/// let params = Lanczos { n_eigen: 2, ..Lanczos::default() };
+/// let (converged, eig_vals, eig_vects) = lanczos_eigenpairs(dim, |v, out| apply(v, out), &params);
+/// ```
+pub fn lanczos_eigenpairs<T, F>(
+    dimension: usize,
+    apply: F,
+    params: &Lanczos,
+) -> (bool, Vec<T>, Vec<Vec<T>>)
+where
+    T: Scalar,
+    F: Fn(&[T], &mut [T]),
+{
+    // Lanczos vectors and tridiagonal matrix elements
+    let start: Vec<T> = initial_vector(dimension);
+    let mut v_prev: Vec<T> = vec![T::zero(); dimension];
+    let mut v_curr: Vec<T> = start.clone();
+    let mut w: Vec<T> = vec![T::zero(); dimension];
+    let mut basis: Vec<Vec<T>> = Vec::new();
+    let mut alphas: Vec<T> = Vec::new();
+    let mut betas: Vec<T> = Vec::new();
+
+    // Tridiagonal eigenpairs at last iteration, the tolerance being relative
+    // to the largest Ritz value (and at least the rounding errors of T)
+    let tolerance: T = T::from(params.tolerance).unwrap().max(T::epsilon());
+    let mut scale: T = T::one();
+    let mut converged: bool = false;
+    let mut ritz_vals: Vec<T> = Vec::new();
+    let mut ritz_vects: Vec<Vec<T>> = Vec::new();
+
+    // Main Lanczos recurrence
+    for iter in 0..params.max_iter.min(dimension) {
+        if params.reorthogonalize {
+            basis.push(v_curr.clone());
+        }
+
+        // w = H v_j - beta_{j-1} v_{j-1} - alpha_j v_j
+        apply(&v_curr, &mut w);
+        if iter > 0 {
+            axpy(-betas[iter - 1], &v_prev, &mut w);
+        }
+        let alpha: T = dot(&w, &v_curr);
+        axpy(-alpha, &v_curr, &mut w);
+        alphas.push(alpha);
+        scale = scale.max(alpha.abs());
+
+        // Full reorthogonalization (twice is enough)
+        if params.reorthogonalize {
+            for _ in 0..2 {
+                for vector in basis.iter() {
+                    let overlap: T = dot(&w, vector);
+                    axpy(-overlap, vector, &mut w);
+                }
+            }
+        }
+        let beta: T = dot(&w, &w).sqrt();
+
+        // Convergence using the residual norms |beta * s_{j,k}|, checked every
+        // few iterations (and at the last one or when the recurrence stops on
+        // an invariant subspace, which only holds the requested levels if it
+        // is large enough)
+        let last: bool = iter + 1 == params.max_iter.min(dimension);
+        let stopped: bool = beta < tolerance * scale;
+        if stopped || last || (iter + 1) % CHECK_INTERVAL == 0 {
+            (ritz_vals, ritz_vects) = tridiagonal_eigenpairs(&alphas, &betas);
+            scale = ritz_vals
+                .iter()
+                .fold(scale, |acc, ritz| acc.max(ritz.abs()));
+            converged = alphas.len() >= params.n_eigen
+                && (stopped
+                    || ritz_vects
+                        .iter()
+                        .take(params.n_eigen)
+                        .all(|s| (beta * s[iter]).abs() < tolerance * scale));
+            if converged || stopped || last {
+                converged = converged || alphas.len() == dimension;
+                break;
+            }
+        }
+
+        // Next Lanczos vector
+        betas.push(beta);
+        std::mem::swap(&mut v_prev, &mut v_curr);
+        for (v_i, &w_i) in v_curr.iter_mut().zip(w.iter()) {
+            *v_i = w_i / beta;
+        }
+    }
+
+    // Ritz vectors from the stored basis or from a second recurrence pass
+    let n_eigen: usize = params.n_eigen.min(alphas.len());
+    let mut eigen_vects: Vec<Vec<T>> = vec![vec![T::zero(); dimension]; n_eigen];
+    if params.reorthogonalize {
+        for (j, vector) in basis.iter().enumerate() {
+            for (k, eigen_vect) in eigen_vects.iter_mut().enumerate() {
+                axpy(ritz_vects[k][j], vector, eigen_vect);
+            }
+        }
+    } else {
+        let mut v_prev: Vec<T> = vec![T::zero(); dimension];
+        let mut v_curr: Vec<T> = start;
+        for j in 0..alphas.len() {
+            for (k, eigen_vect) in eigen_vects.iter_mut().enumerate() {
+                axpy(ritz_vects[k][j], &v_curr, eigen_vect);
+            }
+            if j + 1 == alphas.len() {
+                break;
+            }
+            apply(&v_curr, &mut w);
+            if j > 0 {
+                axpy(-betas[j - 1], &v_prev, &mut w);
+            }
+            axpy(-alphas[j], &v_curr, &mut w);
+            std::mem::swap(&mut v_prev, &mut v_curr);
+            for (v_i, &w_i) in v_curr.iter_mut().zip(w.iter()) {
+                *v_i = w_i / betas[j];
+            }
+        }
+    }
+
+    // Normalizing eigenvectors
+    for eigen_vect in eigen_vects.iter_mut() {
+        let norm: T = dot(eigen_vect, eigen_vect).sqrt();
+        eigen_vect.iter_mut().for_each(|x| *x = *x / norm);
+    }
+    ritz_vals.truncate(n_eigen);
+    (converged, ritz_vals, eigen_vects)
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use crate::lanczos::{lanczos_eigenpairs, Lanczos};
+
+    /// Applies the diagonal matrix with 1 on the first half of the diagonal and
+    /// 2 on the second half (two distinct eigenvalues).
+    fn apply_two_levels(vector: &[f64], output: &mut [f64]) {
+        let n: usize = vector.len();
+        for i in 0..n {
+            output[i] = if i < n / 2 { vector[i] } else { 2. * vector[i] };
+        }
+    }
+
+    /// Applies the tridiagonal matrix with 2 on the diagonal and -1 on the
+    /// off-diagonals (eigenvalues 2 - 2cos(k pi / (n + 1))).
+    fn apply_laplacian(vector: &[f64], output: &mut [f64]) {
+        let n: usize = vector.len();
+        for i in 0..n {
+            output[i] = 2. * vector[i];
+            if i > 0 {
+                output[i] -= vector[i - 1];
+            }
+            if i + 1 < n {
+                output[i] -= vector[i + 1];
+            }
+        }
+    }
+
+    #[test]
+    fn test_lanczos_laplacian() {
+        let n: usize = 50;
+        let exact = |k: usize| 2. - 2. * (k as f64 * std::f64::consts::PI / (n + 1) as f64).cos();
+        for reorthogonalize in [false, true] {
+            let params: Lanczos = Lanczos {
+                n_eigen: 2,
+                reorthogonalize,
+                ..Lanczos::default()
+            };
+            let (converged, eig_vals, eig_vects) = lanczos_eigenpairs(n, apply_laplacian, &params);
+            assert!(converged);
+            assert_eq!(2, eig_vals.len());
+            assert!((eig_vals[0] - exact(1)).abs() < 1e-8);
+            assert!((eig_vals[1] - exact(2)).abs() < 1e-8);
+
+            // Eigenvector residual |H x - E x|
+            let mut image: Vec<f64> = vec![0.; n];
+            apply_laplacian(&eig_vects[0], &mut image);
+            let residual: f64 = image
+                .iter()
+                .zip(eig_vects[0].iter())
+                .map(|(hx, x)| (hx - eig_vals[0] * x).powi(2))
+                .sum();
+            assert!(residual.sqrt() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_lanczos_single_precision() {
+        // Diagonal matrix diag(0, 1, ..., 999) in single precision, whose
+        // residuals can't reach the default (double precision) tolerance
+        let apply = |vector: &[f32], output: &mut [f32]| {
+            for (i, (out_i, v_i)) in output.iter_mut().zip(vector).enumerate() {
+                *out_i = i as f32 * v_i;
+            }
+        };
+        let params: Lanczos = Lanczos {
+            reorthogonalize: true,
+            ..Lanczos::default()
+        };
+        let (converged, eig_vals, _) = lanczos_eigenpairs(1000, apply, &params);
+        assert!(converged);
+        assert!(eig_vals[0].abs() < 1e-3);
+    }
+
+    #[test]
+    fn test_lanczos_invariant_subspace() {
+        // The Krylov space of two distinct eigenvalues stops after two steps
+        let params: Lanczos = Lanczos {
+            n_eigen: 3,
+            ..Lanczos::default()
+        };
+        let (converged, eig_vals, eig_vects) = lanczos_eigenpairs(10, apply_two_levels, &params);
+        assert!(!converged);
+        assert_eq!(2, eig_vals.len());
+        assert_eq!(2, eig_vects.len());
+        assert!((eig_vals[0] - 1.).abs() < 1e-10);
+        assert!((eig_vals[1] - 2.).abs() < 1e-10);
+
+        // Enough levels inside the subspace
+        let params: Lanczos = Lanczos {
+            n_eigen: 2,
+            ..Lanczos::default()
+        };
+        let (converged, eig_vals, _) = lanczos_eigenpairs(10, apply_two_levels, &params);
+        assert!(converged);
+        assert_eq!(2, eig_vals.len());
+    }
+}
@@ -0,0 +1,762 @@
+// This module defines the clusters on which the Hubbard model is solved.
+//
+// Lattice objects are finite clusters of the square lattice with periodic
+// boundary conditions. A cluster is defined by two periods (a1, a2): two sites
+// whose coordinates differ by a combination of a1 and a2 are identified. For
+// example, the 1D ring of n sites has periods (n, 0) and (0, 1), the Lx x Ly
+// square cluster has periods (Lx, 0) and (0, Ly) and the tilted (Betts)
+// clusters of N = a^2 + b^2 sites have periods (a, b) and (-b, a)
+//
+//              8 sites: (2, 2)    10 sites: (3, 1)    16 sites: (4, 0)
+//
+// Sites are numbered row by row (x first) inside the cluster and the
+// nearest-neighbours bonds (including the ones wrapping around the periodic
+// boundaries) are listed as pairs of sites (i, j) with i < j, once per winding
+// (the two sites ring has two bonds, one of them crossing the boundary).
+//
+// Longer range hoppings (t', t'', ...) are added by neighbour shells: pairs of
+// sites are grouped by their distance, measured between the closest periodic
+// images, so that shell 1 holds the nearest neighbours, shell 2 the next
+// nearest neighbours (diagonals of the square lattice, second neighbours of
+// the ring), and so on. When several images of a site are equally close (the
+// opposite site of a ring with an even number of sites for example), one bond
+// is added per image.
+//
+// Each bond (i, j) also records its winding (w1, w2), the number of times it
+// crosses the boundaries of the cluster along a1 and a2 when going from site i
+// to site j. The boundary conditions along each period are either open (the
+// crossing bonds are removed), periodic, antiperiodic or twisted by an angle
+// theta, in which case a fermion hopping from i to j picks up the phase
+//
+//                  exp(i (w1 theta_1 + w2 theta_2))
+//
+// (theta = pi for antiperiodic boundaries). Only periodic and antiperiodic
+// boundaries keep the hamiltonian real.
+//
+// Arbitrary clusters (ladders, molecules, ...) can also be defined from an
+// explicit list of bonds (i, j, t_ij) and per-site parameters, either in the
+// code (Lattice::from_bonds) or from a text file (Lattice::from_file) such as
+//
+//      # Three sites molecule
+//      bond 0 1 1.0
+//      bond 1 2 0.5 2.0
+//      bond 0 2 0.0 0.5
+//      site 2 -1.0 1.5
+//      field 0 0.25
+//
+// where 'bond i j t_ij [v_ij]' adds a hopping t_ij and a density-density
+// repulsion v_ij (1 when omitted) between sites i and j, 'site i e_i u_i' sets
+// the on-site energy e_i and interaction factor u_i of site i and
+// 'field i h_i' sets the local magnetic field h_i of site i. Hoppings and
+// interactions are given in units of the Hubbard model 't', 'u' and 'v'
+// (t_ij = 1, v_ij = 1 and u_i = 1 by default) while on-site energies and
+// fields are absolute (e_i = 0 and h_i = 0 by default). Longer range
+// repulsions are bonds without hopping (t_ij = 0).
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Boundary {
+    Open,
+    Periodic,
+    Antiperiodic,
+    /// Twist angle (in radians)
+    Twisted(f64),
+}
+
+impl Boundary {
+    /// Phase picked up when crossing the boundary once (None if the bonds
+    /// crossing the boundary are removed).
+    pub fn angle(&self) -> Option<f64> {
+        match self {
+            Boundary::Open => None,
+            Boundary::Periodic => Some(0.),
+            Boundary::Antiperiodic => Some(PI),
+            Boundary::Twisted(theta) => Some(*theta),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bond {
+    // Public attributes
+    pub sites: (u32, u32),
+    pub hopping: f64,
+    pub repulsion: f64,
+    pub winding: (i32, i32),
+}
+
+#[derive(Debug, Clone)]
+pub struct Lattice {
+    // Public attributes
+    pub n_sites: u32,
+    pub coordinates: Vec<(i32, i32)>,
+    pub periods: Option<[(i32, i32); 2]>,
+    pub boundaries: [Boundary; 2],
+    pub bonds: Vec<Bond>,
+    pub site_energies: Vec<f64>,
+    pub site_interactions: Vec<f64>,
+    pub site_fields: Vec<f64>,
+}
+
+impl Lattice {
+    /// Builds the periodic cluster defined by the periods 'a1' and 'a2'.
+    fn from_periods(a1: (i32, i32), a2: (i32, i32)) -> Lattice {
+        // Area of the cluster (number of sites)
+        let area: i32 = (a1.0 * a2.1 - a1.1 * a2.0).abs();
+        assert!(area > 0, "Periods {:?} and {:?} are colinear", a1, a2);
+        let mut lattice: Lattice = Lattice {
+            coordinates: Vec::new(),
+            periods: Some([a1, a2]),
+            ..Lattice::from_bonds(area as u32, Vec::new())
+        };
+
+        // Listing the sites inside the cluster (row by row)
+        let span: i32 = a1.0.abs() + a1.1.abs() + a2.0.abs() + a2.1.abs();
+        for y in -span..span + 1 {
+            for x in -span..span + 1 {
+                let site: (i32, i32) = lattice.reduce((x, y));
+                if !lattice.coordinates.contains(&site) {
+                    lattice.coordinates.push(site);
+                }
+            }
+        }
+        lattice.coordinates.sort_by_key(|&(x, y)| (y, x));
+
+        // Nearest-neighbours bonds (x and y directions)
+        for site_i in 0..lattice.n_sites {
+            let (x, y): (i32, i32) = lattice.coordinates[site_i as usize];
+            for (dx, dy) in [(1, 0), (0, 1)] {
+                let (site, winding) = lattice.wrap((x + dx, y + dy));
+                let site_j: u32 =
+                    lattice.coordinates.iter().position(|&c| c == site).unwrap() as u32;
+                let bond: Bond = if site_i < site_j {
+                    Bond {
+                        sites: (site_i, site_j),
+                        hopping: 1.,
+                        repulsion: 1.,
+                        winding,
+                    }
+                } else {
+                    Bond {
+                        sites: (site_j, site_i),
+                        hopping: 1.,
+                        repulsion: 1.,
+                        winding: (-winding.0, -winding.1),
+                    }
+                };
+                if site_i != site_j
+                    && lattice
+                        .bonds
+                        .iter()
+                        .all(|b| (b.sites, b.winding) != (bond.sites, bond.winding))
+                {
+                    lattice.bonds.push(bond);
+                }
+            }
+        }
+        lattice
+    }
+
+    /// Defines an arbitrary cluster of 'n_sites' sites from its list of bonds.
+    /// On-site energies and fields are set to 0, interaction factors to 1 and
+    /// the boundaries are periodic.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// // Two legs ladder with rungs twice as strong as the legs
+    /// let bonds: Vec<Bond> = vec![
+    ///     Bond { sites: (0, 1), hopping: 1., repulsion: 1., winding: (0, 0) },
+    ///     Bond { sites: (2, 3), hopping: 1., repulsion: 1., winding: (0, 0) },
+    ///     Bond { sites: (0, 2), hopping: 2., repulsion: 1., winding: (0, 0) },
+    ///     Bond { sites: (1, 3), hopping: 2., repulsion: 1., winding: (0, 0) },
+    /// ];
+    /// let lattice: Lattice = Lattice::from_bonds(4, bonds);
+    /// ```
+    pub fn from_bonds(n_sites: u32, bonds: Vec<Bond>) -> Lattice {
+        for bond in bonds.iter() {
+            assert!(
+                bond.sites.0 < n_sites && bond.sites.1 < n_sites && bond.sites.0 != bond.sites.1,
+                "Invalid bond {:?} on {} sites",
+                bond.sites,
+                n_sites
+            );
+        }
+        Lattice {
+            n_sites,
+            coordinates: Vec::new(),
+            periods: None,
+            boundaries: [Boundary::Periodic; 2],
+            bonds,
+            site_energies: vec![0.; n_sites as usize],
+            site_interactions: vec![1.; n_sites as usize],
+            site_fields: vec![0.; n_sites as usize],
+        }
+    }
+
+    /// Reads an arbitrary cluster from a text file containing 'bond i j t_ij',
+    /// 'site i e_i u_i' and 'field i h_i' rows (see module description). The
+    /// number of sites is given by the largest site index found in the file.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let lattice: Lattice = Lattice::from_file("./Data/ladder.txt").unwrap();
+    /// ```
+    pub fn from_file(path: &str) -> Result<Lattice, Box<dyn Error>> {
+        let mut rdr = csv::ReaderBuilder::new()
+            .has_headers(false)
+            .flexible(true)
+            .delimiter(b' ')
+            .comment(Some(b'#'))
+            .trim(csv::Trim::All)
+            .from_path(path)?;
+
+        // Parsing bonds and sites parameters
+        let mut bonds: Vec<Bond> = Vec::new();
+        let mut sites: Vec<(u32, f64, f64)> = Vec::new();
+        let mut local_fields: Vec<(u32, f64)> = Vec::new();
+        for record in rdr.records() {
+            let record: csv::StringRecord = record?;
+            let fields: Vec<&str> = record.iter().filter(|f| !f.is_empty()).collect();
+            match fields.as_slice() {
+                ["bond", i, j, t_ij] => bonds.push(Bond {
+                    sites: (i.parse()?, j.parse()?),
+                    hopping: t_ij.parse()?,
+                    repulsion: 1.,
+                    winding: (0, 0),
+                }),
+                ["bond", i, j, t_ij, v_ij] => bonds.push(Bond {
+                    sites: (i.parse()?, j.parse()?),
+                    hopping: t_ij.parse()?,
+                    repulsion: v_ij.parse()?,
+                    winding: (0, 0),
+                }),
+                ["site", i, e_i, u_i] => sites.push((i.parse()?, e_i.parse()?, u_i.parse()?)),
+                ["field", i, h_i] => local_fields.push((i.parse()?, h_i.parse()?)),
+                [] => continue,
+                _ => return Err(format!("Invalid lattice row: {:?}", fields).into()),
+            }
+        }
+
+        // Empty (or comment only) files define no cluster
+        if bonds.is_empty() && sites.is_empty() {
+            return Err(String::from("No bond or site rows in the lattice file").into());
+        }
+
+        // Fermions can't hop from a site to itself
+        if let Some(bond) = bonds.iter().find(|b| b.sites.0 == b.sites.1) {
+            return Err(format!("Invalid bond {:?} from a site to itself", bond.sites).into());
+        }
+
+        // Number of sites from the largest index
+        let n_sites: u32 = bonds
+            .iter()
+            .flat_map(|b| [b.sites.0, b.sites.1])
+            .chain(sites.iter().map(|s| s.0))
+            .chain(local_fields.iter().map(|f| f.0))
+            .max()
+            .map_or(0, |i| i + 1);
+        let mut lattice: Lattice = Lattice::from_bonds(n_sites, bonds);
+        for (site, energy, interaction) in sites {
+            lattice.site_energies[site as usize] = energy;
+            lattice.site_interactions[site as usize] = interaction;
+        }
+        for (site, field) in local_fields {
+            lattice.site_fields[site as usize] = field;
+        }
+        Ok(lattice)
+    }
+
+    /// Defines the periodic 1D ring of 'n_sites' sites.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let lattice: Lattice = Lattice::chain(6);
+    /// ```
+    pub fn chain(n_sites: u32) -> Lattice {
+        Lattice::from_periods((n_sites as i32, 0), (0, 1))
+    }
+
+    /// Defines the two sites molecule (open chain of two sites) used by the
+    /// tests, whose single bond is not doubled by the periodic boundaries.
+    #[cfg(test)]
+    pub fn dimer() -> Lattice {
+        let mut lattice: Lattice = Lattice::chain(2);
+        lattice.boundaries = [Boundary::Open; 2];
+        lattice
+    }
+
+    /// Defines the periodic 'lx' x 'ly' square cluster.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let lattice: Lattice = Lattice::square(4, 4);
+    /// ```
+    pub fn square(lx: u32, ly: u32) -> Lattice {
+        Lattice::from_periods((lx as i32, 0), (0, ly as i32))
+    }
+
+    /// Defines the tilted (Betts) square cluster of 'n_sites' sites with
+    /// periods (a, b) and (-b, a), where n_sites = a^2 + b^2.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let lattice: Lattice = Lattice::betts(10);
+    /// ```
+    pub fn betts(n_sites: u32) -> Lattice {
+        match Lattice::tilted_period(n_sites) {
+            Some((a, b)) => Lattice::from_periods((a, b), (-b, a)),
+            None => panic!("No tilted square cluster has {} sites", n_sites),
+        }
+    }
+
+    /// Outputs the period (a, b) with a >= b >= 0 of the tilted square cluster
+    /// of 'n_sites' sites (a^2 + b^2 = n_sites), if any.
+    pub fn tilted_period(n_sites: u32) -> Option<(i32, i32)> {
+        let n: i32 = n_sites as i32;
+        (1..n + 1)
+            .flat_map(|a| (0..a + 1).map(move |b| (a, b)))
+            .find(|(a, b)| a * a + b * b == n)
+    }
+
+    /// Brings given coordinates back inside the cluster using its periods.
+    ///
+    /// Panics if the cluster was not defined from periods.
+    pub fn reduce(&self, position: (i32, i32)) -> (i32, i32) {
+        self.wrap(position).0
+    }
+
+    /// Brings given coordinates back inside the cluster and outputs the number
+    /// of periods (m, n) removed, position = reduced + m a1 + n a2.
+    fn wrap(&self, position: (i32, i32)) -> ((i32, i32), (i32, i32)) {
+        let [a1, a2]: [(i32, i32); 2] = self.periods.expect("Cluster has no periods");
+        let det: i32 = a1.0 * a2.1 - a1.1 * a2.0;
+
+        // Coordinates in the basis of the periods (scaled by det)
+        let u: i32 = position.0 * a2.1 - position.1 * a2.0;
+        let v: i32 = a1.0 * position.1 - a1.1 * position.0;
+        let m: i32 = (u * det.signum()).div_euclid(det.abs());
+        let n: i32 = (v * det.signum()).div_euclid(det.abs());
+        (
+            (
+                position.0 - m * a1.0 - n * a2.0,
+                position.1 - m * a1.1 - n * a2.1,
+            ),
+            (m, n),
+        )
+    }
+
+    /// Outputs the index of the site at given coordinates (periodic images
+    /// included).
+    pub fn site_at(&self, position: (i32, i32)) -> u32 {
+        let site: (i32, i32) = self.reduce(position);
+        self.coordinates.iter().position(|&c| c == site).unwrap() as u32
+    }
+
+    /// Outputs the squared distance between two sites, measured between their
+    /// closest periodic images, along with the windings of these images (the
+    /// image of site j at r_j + w1 a1 + w2 a2 has winding (w1, w2)).
+    ///
+    /// Panics if the cluster was not defined from periods.
+    pub fn image_distance(&self, site_i: u32, site_j: u32) -> (i32, Vec<(i32, i32)>) {
+        let [a1, a2]: [(i32, i32); 2] = self.periods.expect("Cluster has no periods");
+        let (x_i, y_i): (i32, i32) = self.coordinates[site_i as usize];
+        let (x_j, y_j): (i32, i32) = self.coordinates[site_j as usize];
+        let ((dx, dy), (m_0, n_0)) = self.wrap((x_j - x_i, y_j - y_i));
+
+        // Squared distances to the images around the reduced separation
+        let images: Vec<(i32, (i32, i32))> = (-2..3)
+            .flat_map(|m| (-2..3).map(move |n| (m, n)))
+            .map(|(m, n)| {
+                let x: i32 = dx + m * a1.0 + n * a2.0;
+                let y: i32 = dy + m * a1.1 + n * a2.1;
+                (x * x + y * y, (m - m_0, n - n_0))
+            })
+            .collect();
+        let minimum: i32 = images.iter().map(|&(d, _)| d).min().unwrap();
+        let windings: Vec<(i32, i32)> = images
+            .into_iter()
+            .filter(|&(d, _)| d == minimum)
+            .map(|(_, winding)| winding)
+            .collect();
+        (minimum, windings)
+    }
+
+    /// Lists the pairs of sites (i, j) with i < j of each neighbour shell, by
+    /// increasing distance (the first shell holds the nearest neighbours).
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// // Diagonals of the 4 x 4 square cluster
+    /// let diagonals: Vec<(u32, u32)> = Lattice::square(4, 4).neighbour_shells()[1].clone();
+    /// ```
+    pub fn neighbour_shells(&self) -> Vec<Vec<(u32, u32)>> {
+        let mut pairs: Vec<(i32, (u32, u32))> = (0..self.n_sites)
+            .flat_map(|i| (i + 1..self.n_sites).map(move |j| (i, j)))
+            .map(|(i, j)| (self.image_distance(i, j).0, (i, j)))
+            .collect();
+        pairs.sort_by_key(|&(distance, _)| distance);
+
+        // Grouping the pairs of equal distance
+        let mut shells: Vec<Vec<(u32, u32)>> = Vec::new();
+        let mut last_distance: Option<i32> = None;
+        for (distance, pair) in pairs {
+            if last_distance != Some(distance) {
+                shells.push(Vec::new());
+                last_distance = Some(distance);
+            }
+            shells.last_mut().unwrap().push(pair);
+        }
+        shells
+    }
+
+    /// Sets the hopping (in units of 't') between the sites of given neighbour
+    /// shell: 1 for the nearest neighbours, 2 for t', 3 for t'' and so on.
+    /// Each closest image gets its own bond (like the nearest neighbours of the
+    /// two sites ring): existing bonds get the hopping while the bonds added
+    /// for longer range shells carry no extended repulsion.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// // t-t' square cluster with t' = -0.3 t
+    /// let mut lattice: Lattice = Lattice::square(4, 4);
+    /// lattice.set_shell_hopping(2, -0.3);
+    /// ```
+    pub fn set_shell_hopping(&mut self, shell: usize, hopping: f64) {
+        let shells: Vec<Vec<(u32, u32)>> = self.neighbour_shells();
+        assert!(
+            shell >= 1 && shell <= shells.len(),
+            "Cluster of {} sites has no neighbour shell {}",
+            self.n_sites,
+            shell
+        );
+        for &sites in shells[shell - 1].iter() {
+            for winding in self.image_distance(sites.0, sites.1).1 {
+                match self
+                    .bonds
+                    .iter_mut()
+                    .find(|b| (b.sites, b.winding) == (sites, winding))
+                {
+                    Some(bond) => bond.hopping = hopping,
+                    None if hopping != 0. => self.bonds.push(Bond {
+                        sites,
+                        hopping,
+                        repulsion: 0.,
+                        winding,
+                    }),
+                    None => (),
+                }
+            }
+        }
+    }
+
+    /// Outputs the phase picked up by a fermion hopping along given bond (from
+    /// its first site to its second site), or None if the bond crosses an open
+    /// boundary.
+    pub fn bond_phase(&self, bond: &Bond) -> Option<f64> {
+        let mut phase: f64 = 0.;
+        for (winding, boundary) in [bond.winding.0, bond.winding.1]
+            .iter()
+            .zip(self.boundaries.iter())
+        {
+            if *winding != 0 {
+                phase += *winding as f64 * boundary.angle()?;
+            }
+        }
+        Some(phase)
+    }
+
+    /// Checks if the hoppings of the cluster are real (no bond picks up a
+    /// phase other than 0 or pi).
+    pub fn is_real(&self) -> bool {
+        self.bonds
+            .iter()
+            .filter_map(|bond| self.bond_phase(bond))
+            .all(|phase| phase.sin().abs() < 1e-12)
+    }
+
+    /// Splits the sites of the cluster into two sublattices A and B such that
+    /// the hoppings only link sites of different sublattices, outputting the
+    /// sign epsilon_i (1 on A, -1 on B, site 0 being on A) of each site. None
+    /// if the cluster is not bipartite (odd rings, frustrating hoppings).
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// assert_eq!(Some(vec![1, -1, 1, -1]), Lattice::chain(4).sublattice_signs());
+    /// assert_eq!(None, Lattice::chain(5).sublattice_signs());
+    /// ```
+    pub fn sublattice_signs(&self) -> Option<Vec<i32>> {
+        let mut signs: Vec<i32> = vec![0; self.n_sites as usize];
+        let mut neighbours: Vec<Vec<u32>> = vec![Vec::new(); self.n_sites as usize];
+        for bond in self.bonds.iter() {
+            if bond.hopping != 0. && self.bond_phase(bond).is_some() {
+                let (site_i, site_j): (u32, u32) = bond.sites;
+                neighbours[site_i as usize].push(site_j);
+                neighbours[site_j as usize].push(site_i);
+            }
+        }
+
+        // Colouring each connected part of the cluster from its first site
+        for start in 0..self.n_sites as usize {
+            if signs[start] != 0 {
+                continue;
+            }
+            signs[start] = 1;
+            let mut queue: Vec<usize> = vec![start];
+            while let Some(site) = queue.pop() {
+                for &neighbour in neighbours[site].iter() {
+                    let neighbour: usize = neighbour as usize;
+                    if signs[neighbour] == 0 {
+                        signs[neighbour] = -signs[site];
+                        queue.push(neighbour);
+                    } else if signs[neighbour] == signs[site] {
+                        return None;
+                    }
+                }
+            }
+        }
+        Some(signs)
+    }
+
+    /// Checks if the cluster is invariant under the translation of the ring
+    /// (site i -> site i + 1 modulo n_sites). Open, antiperiodic and twisted
+    /// boundaries break it.
+    pub fn is_translation_invariant(&self) -> bool {
+        let translation: Vec<u32> = (0..self.n_sites).map(|i| (i + 1) % self.n_sites).collect();
+        self.is_invariant(&translation)
+    }
+
+    /// Checks if the cluster is invariant under given permutation of its sites
+    /// (site i -> site permutation[i]): the permuted sites must carry the same
+    /// on-site parameters and the permuted bonds the same (real) hoppings and
+    /// repulsions.
+    pub fn is_invariant(&self, permutation: &[u32]) -> bool {
+        let same = |values: &[f64]| {
+            (0..self.n_sites as usize)
+                .all(|i| (values[permutation[i] as usize] - values[i]).abs() < 1e-12)
+        };
+        if !(same(&self.site_energies) && same(&self.site_interactions) && same(&self.site_fields))
+        {
+            return false;
+        }
+
+        // Hoppings (with their boundary signs) and repulsions of each pair of
+        // sites, the bonds removed by open boundaries being skipped
+        let mut pairs: HashMap<(u32, u32), (f64, f64)> = HashMap::new();
+        for bond in self.bonds.iter() {
+            let hopping: f64 = match self.bond_phase(bond) {
+                Some(phase) if phase.sin().abs() < 1e-12 => bond.hopping * phase.cos(),
+                Some(_) => return false,
+                None => continue,
+            };
+            let (site_i, site_j): (u32, u32) = bond.sites;
+            let pair: &mut (f64, f64) = pairs
+                .entry((site_i.min(site_j), site_i.max(site_j)))
+                .or_insert((0., 0.));
+            pair.0 += hopping;
+            pair.1 += bond.repulsion;
+        }
+        pairs
+            .iter()
+            .all(|(&(site_i, site_j), &(hopping, repulsion))| {
+                let (a, b): (u32, u32) =
+                    (permutation[site_i as usize], permutation[site_j as usize]);
+                let (t_ab, v_ab) = pairs
+                    .get(&(a.min(b), a.max(b)))
+                    .copied()
+                    .unwrap_or((0., 0.));
+                (t_ab - hopping).abs() < 1e-12 && (v_ab - repulsion).abs() < 1e-12
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use std::f64::consts::PI;
+    use std::io::Write;
+
+    use crate::lattice::{Bond, Boundary, Lattice};
+
+    #[test]
+    fn test_chain_bonds() {
+        let ring: Lattice = Lattice::chain(4);
+        let sites: Vec<(u32, u32)> = ring.bonds.iter().map(|b| b.sites).collect();
+        assert_eq!(vec![(0, 1), (1, 2), (2, 3), (0, 3)], sites);
+
+        // Two sites ring has one bond per winding
+        let windings: Vec<(i32, i32)> = Lattice::chain(2).bonds.iter().map(|b| b.winding).collect();
+        assert_eq!(vec![(0, 0), (-1, 0)], windings);
+
+        // Antiperiodic boundaries act on the bond crossing the boundary
+        let mut antiperiodic: Lattice = Lattice::chain(2);
+        antiperiodic.boundaries[0] = Boundary::Antiperiodic;
+        let phases: Vec<Option<f64>> = antiperiodic
+            .bonds
+            .iter()
+            .map(|bond| antiperiodic.bond_phase(bond))
+            .collect();
+        assert_eq!(vec![Some(0.), Some(-PI)], phases);
+    }
+
+    #[test]
+    fn test_sublattices() {
+        assert_eq!(
+            Some(vec![1, -1, 1, -1]),
+            Lattice::chain(4).sublattice_signs()
+        );
+        assert_eq!(None, Lattice::chain(5).sublattice_signs());
+        // Open odd chain and checkerboard of the square cluster
+        let mut open: Lattice = Lattice::chain(5);
+        open.boundaries = [Boundary::Open, Boundary::Open];
+        assert_eq!(Some(vec![1, -1, 1, -1, 1]), open.sublattice_signs());
+        let signs: Vec<i32> = Lattice::square(4, 4).sublattice_signs().unwrap();
+        assert_eq!(8, signs.iter().filter(|&&s| s > 0).count());
+        // Diagonal hoppings frustrate the square cluster
+        let mut frustrated: Lattice = Lattice::square(4, 4);
+        frustrated.set_shell_hopping(2, -0.3);
+        assert_eq!(None, frustrated.sublattice_signs());
+    }
+
+    #[test]
+    fn test_translation_invariance() {
+        let mut ring: Lattice = Lattice::chain(6);
+        assert!(ring.is_translation_invariant());
+        ring.set_shell_hopping(3, 0.5);
+        assert!(ring.is_translation_invariant());
+
+        // Boundaries and inequivalent sites break the translations
+        for boundary in [Boundary::Open, Boundary::Antiperiodic] {
+            ring.boundaries = [boundary; 2];
+            assert!(!ring.is_translation_invariant());
+        }
+        let mut ring: Lattice = Lattice::chain(6);
+        ring.site_energies[2] = 1.;
+        assert!(!ring.is_translation_invariant());
+        ring.site_energies[2] = 0.;
+        ring.bonds[0].repulsion = 2.;
+        assert!(!ring.is_translation_invariant());
+
+        // Repulsions across open boundaries are cut along with the hoppings
+        let mut ring: Lattice = Lattice::chain(6);
+        ring.bonds.iter_mut().for_each(|bond| bond.hopping = 0.);
+        assert!(ring.is_translation_invariant());
+        ring.boundaries = [Boundary::Open; 2];
+        assert!(!ring.is_translation_invariant());
+    }
+
+    #[test]
+    fn test_lattice_from_file() {
+        // Writing a three sites molecule inside a temporary file
+        let path = std::env::temp_dir().join("hubbies_test_lattice.txt");
+        let mut file = std::fs::File::create(&path).unwrap();
+        writeln!(
+            file,
+            "# Three sites molecule\nbond 0 1 1.0\nbond 1 2 0.5 2.0\n\nsite 2 -1.0 1.5\nfield 0 0.25"
+        )
+        .unwrap();
+
+        let lattice: Lattice = Lattice::from_file(path.to_str().unwrap()).unwrap();
+        assert_eq!(3, lattice.n_sites);
+        assert_eq!(
+            Bond {
+                sites: (1, 2),
+                hopping: 0.5,
+                repulsion: 2.,
+                winding: (0, 0)
+            },
+            lattice.bonds[1]
+        );
+        assert_eq!(vec![0., 0., -1.], lattice.site_energies);
+        assert_eq!(vec![1., 1., 1.5], lattice.site_interactions);
+        assert_eq!(vec![0.25, 0., 0.], lattice.site_fields);
+        assert!(lattice.periods.is_none());
+
+        // Bonds from a site to itself are rejected
+        let mut file = std::fs::File::create(&path).unwrap();
+        writeln!(file, "bond 0 1 1.0\nbond 1 1 1.0").unwrap();
+        assert!(Lattice::from_file(path.to_str().unwrap()).is_err());
+
+        // Files without bond or site rows are rejected
+        let mut file = std::fs::File::create(&path).unwrap();
+        writeln!(file, "# Nothing but comments").unwrap();
+        assert!(Lattice::from_file(path.to_str().unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_square_bonds() {
+        let cluster: Lattice = Lattice::square(4, 3);
+        assert_eq!(12, cluster.n_sites);
+        assert_eq!(24, cluster.bonds.len());
+        assert_eq!(5, cluster.site_at((1, 1)));
+        assert_eq!(0, cluster.site_at((4, -3)));
+
+        // Every site has four neighbours
+        for site in 0..cluster.n_sites {
+            let n_neighbours: usize = cluster
+                .bonds
+                .iter()
+                .filter(|b| b.sites.0 == site || b.sites.1 == site)
+                .count();
+            assert_eq!(4, n_neighbours);
+        }
+    }
+
+    #[test]
+    fn test_neighbour_shells() {
+        // Square cluster: 4 neighbours, 4 diagonals and 2 sites at distance 2
+        let mut cluster: Lattice = Lattice::square(4, 4);
+        let shells: Vec<Vec<(u32, u32)>> = cluster.neighbour_shells();
+        assert_eq!(
+            vec![32, 32, 16],
+            shells[..3].iter().map(|s| s.len()).collect::<Vec<_>>()
+        );
+        assert!(shells[1].contains(&(0, 5)));
+        assert!(shells[1].contains(&(0, 15)));
+        assert_eq!((4, vec![(-1, 0), (0, 0)]), cluster.image_distance(0, 2));
+
+        // Diagonals are added, nearest neighbours hoppings are updated
+        cluster.set_shell_hopping(2, -0.3);
+        cluster.set_shell_hopping(1, 0.5);
+        assert_eq!(64, cluster.bonds.len());
+        assert!(cluster.bonds[..32].iter().all(|b| b.hopping == 0.5));
+        assert!(cluster.bonds[32..]
+            .iter()
+            .all(|b| b.hopping == -0.3 && b.repulsion == 0.));
+
+        // Opposite site of the ring has two equally close images
+        let mut ring: Lattice = Lattice::chain(6);
+        ring.set_shell_hopping(3, 0.25);
+        assert_eq!(12, ring.bonds.len());
+        assert_eq!((-1, 0), ring.bonds[6].winding);
+        assert_eq!((0, 0), ring.bonds[7].winding);
+
+        // Nearest neighbours of the length 2 periods keep one bond per winding
+        for default in [Lattice::chain(2), Lattice::square(4, 2)] {
+            let mut lattice: Lattice = default.clone();
+            lattice.set_shell_hopping(1, 1.);
+            assert_eq!(default.bonds, lattice.bonds);
+        }
+    }
+
+    #[test]
+    fn test_betts_clusters() {
+        for n_sites in [8, 10, 16] {
+            let cluster: Lattice = Lattice::betts(n_sites);
+            assert_eq!(n_sites as usize, cluster.coordinates.len());
+            assert_eq!(2 * n_sites as usize, cluster.bonds.len());
+        }
+    }
+}
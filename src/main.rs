@@ -1,21 +1,1006 @@
-// Main module. This is where one should create and solve the Hubbard
-// model for given parameters.
+// Main module. This is where the command-line arguments are parsed and the
+// Hubbard model is created and solved for given parameters.
 
 mod array_utils;
+mod basis;
+mod cli;
+mod config;
+mod disorder;
 mod file_utils;
 mod fock_space;
+mod lanczos;
+mod lattice;
+mod momentum;
+mod sectors;
+mod spin_inversion;
+mod structure;
+mod symmetry;
+mod total_spin;
 
-use crate::fock_space::Hubbard;
+use clap::{CommandFactory, Parser};
+use rayon::prelude::*;
+use rayon::ThreadPoolBuilder;
+use std::f64::consts::PI;
 use std::println;
 use std::time::Instant;
 
+use crate::array_utils::Eigenvectors;
+use crate::basis::State;
+use crate::cli::{Cli, Command, DisorderArgs, Solver, SolverArgs};
+use crate::config::{read_config, write_config};
+use crate::disorder::{mean_and_deviation, Disorder};
+use crate::file_utils::{
+    init_file_writter, init_progress_bar, write_disorder_eigenvalues, write_eigenvalues_header,
+    write_grand_canonical_point, write_labelled_eigenvalues, write_level_average,
+    write_magnetization_point, write_multiplet, write_sector_eigenvalues,
+    write_sector_eigenvectors, write_sweep_eigenvalues, write_symmetry_eigenvalues, AVERAGE_HEADER,
+    DISORDER_HEADER, EIGENVALUES_HEADER, EIGENVECTORS_HEADER, GRAND_CANONICAL_HEADER,
+    MAGNETIZATION_HEADER, MOMENTUM_HEADER, MULTIPLET_HEADER, SWEEP_HEADER, SYMMETRY_HEADER,
+    TWIST_HEADER,
+};
+use crate::fock_space::{eta_squared_term, spin_squared_term, BlockEigenpairs, Hubbard};
+use crate::lattice::{Boundary, Lattice};
+use crate::momentum::MomentumBasis;
+use crate::sectors::{grand_canonical_ground_state, magnetized_ground_state, Sector};
+use crate::spin_inversion::SpinInversionBasis;
+use crate::structure::SectorStructure;
+use crate::symmetry::{Symmetry, SymmetryBasis};
+use crate::total_spin::{casimir_labels, collapse_multiplets, spin_gaps, Multiplet};
+
+/// Finds the lowest eigenvalues of given sector using the requested solver.
+fn lowest_eigenvalues(model: &Hubbard, sector: &Sector, solver: &SolverArgs) -> BlockEigenpairs {
+    match solver.solver {
+        Solver::Lapack => {
+            let mut pairs: BlockEigenpairs =
+                model.get_sector_eigenpairs(sector.n_up, sector.n_down, Eigenvectors::Skip);
+            pairs.eigen_vals.truncate(solver.n_eigen);
+            pairs
+        }
+        Solver::Lanczos => model.get_sector_lanczos(sector.n_up, sector.n_down, &solver.lanczos()),
+    }
+}
+
+/// Writes the lowest eigenvalues of each sector inside 'data_dir/ground_state.csv'
+/// and prints the overall ground state.
+fn run_ground_state(model: &Hubbard, sectors: &[Sector], solver: &SolverArgs, data_dir: &str) {
+    let data_path: String = format!("{}/ground_state.csv", data_dir);
+    let mut writter: csv::Writer<std::fs::File> = init_file_writter(&data_path, false);
+    write_eigenvalues_header(&mut writter).unwrap();
+
+    // Lowest eigenvalues of each sector
+    let mut ground_state: Option<(f64, Sector)> = None;
+    for sector in sectors.iter() {
+        let pairs: BlockEigenpairs = lowest_eigenvalues(model, sector, solver);
+        let (dimension, info) = (sector.dimension(), pairs.info);
+        write_sector_eigenvalues(&mut writter, sector, dimension, info, &pairs.eigen_vals).unwrap();
+        if ground_state.is_none_or(|(energy, _)| pairs.eigen_vals[0] < energy) {
+            ground_state = Some((pairs.eigen_vals[0], *sector));
+        }
+    }
+    if let Some((energy, sector)) = ground_state {
+        println!(
+            "Ground state energy: {} (n_up = {}, n_down = {})",
+            energy, sector.n_up, sector.n_down
+        );
+    }
+}
+
+/// Writes the lowest eigenvalues of each sector for every (t, u) point inside
+/// 'data_dir/sweep.csv'. The structure of the sectors is built once and the
+/// blocks are re-assembled for each point.
+fn run_sweep(
+    model: &Hubbard,
+    sectors: &[Sector],
+    solver: &SolverArgs,
+    points: &[(f64, f64)],
+    data_dir: &str,
+) {
+    let data_path: String = format!("{}/sweep.csv", data_dir);
+    let mut writter: csv::Writer<std::fs::File> = init_file_writter(&data_path, false);
+    writter.write_record(SWEEP_HEADER).unwrap();
+
+    // Sector bases and hoppings (independent of t and u)
+    let structures: Vec<SectorStructure> = sectors
+        .iter()
+        .map(|sector| SectorStructure::new(model, *sector))
+        .collect();
+
+    // Main loop over the parameter points
+    let pb = init_progress_bar(String::from("Sweep"), points.len() as u64);
+    for &(t, u) in points.iter() {
+        for structure in structures.iter() {
+            let mut pairs: BlockEigenpairs = match solver.solver {
+                Solver::Lapack => structure.eigenpairs(t, u, Eigenvectors::Skip),
+                Solver::Lanczos => structure.lanczos(t, u, &solver.lanczos()),
+            };
+            pairs.eigen_vals.truncate(solver.n_eigen);
+            let (dimension, info) = (structure.dimension(), pairs.info);
+            write_sweep_eigenvalues(
+                &mut writter,
+                t,
+                u,
+                &structure.sector,
+                dimension,
+                info,
+                &pairs.eigen_vals,
+            )
+            .unwrap();
+        }
+        pb.inc(1);
+    }
+    pb.finish();
+}
+
+/// Writes the grand-canonical ground state for every chemical potential inside
+/// 'data_dir/grand_canonical.csv'. The lowest eigenvalue of each sector is
+/// computed once (at zero chemical potential) and shifted by -mu * N.
+fn run_grand_canonical(
+    model: &mut Hubbard,
+    sectors: &[Sector],
+    solver: &SolverArgs,
+    mu_values: &[f64],
+    data_dir: &str,
+) {
+    let data_path: String = format!("{}/grand_canonical.csv", data_dir);
+    let mut writter: csv::Writer<std::fs::File> = init_file_writter(&data_path, false);
+    writter.write_record(GRAND_CANONICAL_HEADER).unwrap();
+
+    // Lowest eigenvalue of each sector
+    model.mu = 0.;
+    let lowest: Vec<(Sector, f64)> = sectors
+        .iter()
+        .map(|sector| {
+            (
+                *sector,
+                lowest_eigenvalues(model, sector, solver).eigen_vals[0],
+            )
+        })
+        .collect();
+
+    // Main loop over the chemical potentials
+    for &mu in mu_values.iter() {
+        if let Some((sector, energy)) = grand_canonical_ground_state(&lowest, mu) {
+            write_grand_canonical_point(&mut writter, mu, &sector, energy).unwrap();
+        }
+    }
+}
+
+/// Writes the lowest eigenvalues of each sector for every realization of the
+/// disorder inside 'data_dir/disorder.csv' and their averages over the
+/// realizations inside 'data_dir/disorder_average.csv'. The realizations are
+/// solved in parallel and written in order.
+fn run_disorder(
+    model: &Hubbard,
+    sectors: &[Sector],
+    solver: &SolverArgs,
+    args: &DisorderArgs,
+    data_dir: &str,
+) {
+    let disorder: Disorder = args.disorder();
+
+    // Seed of each realization (wrapping around the largest seeds)
+    let seeds: Vec<u64> = (0..args.realizations)
+        .map(|realization| args.seed.wrapping_add(realization as u64))
+        .collect();
+
+    // Lowest eigenvalues of each sector for every realization
+    let pb = init_progress_bar(String::from("Disorder"), args.realizations as u64);
+    let realizations: Vec<Vec<BlockEigenpairs>> = seeds
+        .par_iter()
+        .map(|&seed| {
+            let disordered: Hubbard = model.with_lattice(disorder.realize(&model.lattice, seed));
+            let pairs: Vec<BlockEigenpairs> = sectors
+                .iter()
+                .map(|sector| lowest_eigenvalues(&disordered, sector, solver))
+                .collect();
+            pb.inc(1);
+            pairs
+        })
+        .collect();
+    pb.finish();
+
+    // Eigenvalues of the realizations
+    let data_path: String = format!("{}/disorder.csv", data_dir);
+    let mut writter: csv::Writer<std::fs::File> = init_file_writter(&data_path, false);
+    writter.write_record(DISORDER_HEADER).unwrap();
+    for (realization, (pairs, &seed)) in realizations.iter().zip(seeds.iter()).enumerate() {
+        for (sector, pair) in sectors.iter().zip(pairs.iter()) {
+            write_disorder_eigenvalues(
+                &mut writter,
+                realization,
+                seed,
+                sector,
+                pair.info,
+                &pair.eigen_vals,
+            )
+            .unwrap();
+        }
+    }
+
+    // Averages over the realizations
+    let data_path: String = format!("{}/disorder_average.csv", data_dir);
+    write_averages(sectors, &realizations, &data_path);
+}
+
+/// Writes the average over the samples (disorder realizations or twist angles)
+/// of the lowest eigenvalues of each sector inside given file and prints the
+/// average ground state energy (and energy per site).
+fn write_averages(sectors: &[Sector], samples: &[Vec<BlockEigenpairs>], data_path: &String) {
+    let mut writter: csv::Writer<std::fs::File> = init_file_writter(data_path, false);
+    writter.write_record(AVERAGE_HEADER).unwrap();
+    for (index, sector) in sectors.iter().enumerate() {
+        let n_levels: usize = samples
+            .iter()
+            .map(|pairs| pairs[index].eigen_vals.len())
+            .min()
+            .unwrap_or(0);
+        for level in 0..n_levels {
+            let energies: Vec<f64> = samples
+                .iter()
+                .map(|pairs| pairs[index].eigen_vals[level])
+                .collect();
+            let (mean, deviation) = mean_and_deviation(&energies);
+            write_level_average(&mut writter, sector, level, energies.len(), mean, deviation)
+                .unwrap();
+        }
+    }
+
+    // Ground state energy of the samples
+    let ground_energies: Vec<f64> = samples
+        .iter()
+        .filter_map(|pairs| {
+            pairs
+                .iter()
+                .map(|pair| pair.eigen_vals[0])
+                .min_by(|a, b| a.partial_cmp(b).unwrap())
+        })
+        .collect();
+    if !ground_energies.is_empty() {
+        let (mean, deviation) = mean_and_deviation(&ground_energies);
+        let n_sites: f64 = sectors[0].n_sites as f64;
+        println!(
+            "Average ground state energy: {} (deviation {}), {} per site",
+            mean,
+            deviation,
+            mean / n_sites
+        );
+    }
+}
+
+/// Writes the lowest eigenvalues of each sector for every point of the grid of
+/// twist angles (theta_1, theta_2) inside 'data_dir/twist.csv' (angles in units
+/// of pi) and their twist averages inside 'data_dir/twist_average.csv'. The
+/// second angle is only varied if some bonds cross the boundaries along the
+/// second period.
+fn run_twist(
+    model: &Hubbard,
+    sectors: &[Sector],
+    solver: &SolverArgs,
+    twist_values: &[f64],
+    data_dir: &str,
+) {
+    let two_dimensional: bool = model.lattice.bonds.iter().any(|b| b.winding.1 != 0);
+    let points: Vec<(f64, f64)> = if two_dimensional {
+        twist_values
+            .iter()
+            .flat_map(|&theta_1| twist_values.iter().map(move |&theta_2| (theta_1, theta_2)))
+            .collect()
+    } else {
+        twist_values.iter().map(|&theta_1| (theta_1, 0.)).collect()
+    };
+
+    // Lowest eigenvalues of each sector for every twist (complex blocks)
+    let pb = init_progress_bar(String::from("Twists"), points.len() as u64);
+    let spectra: Vec<Vec<BlockEigenpairs>> = points
+        .par_iter()
+        .map(|&(theta_1, theta_2)| {
+            let mut lattice: Lattice = model.lattice.clone();
+            lattice.boundaries = [
+                Boundary::Twisted(theta_1 * PI),
+                Boundary::Twisted(theta_2 * PI),
+            ];
+            let twisted: Hubbard = model.with_lattice(lattice);
+            let pairs: Vec<BlockEigenpairs> = sectors
+                .iter()
+                .map(|sector| lowest_eigenvalues(&twisted, sector, solver))
+                .collect();
+            pb.inc(1);
+            pairs
+        })
+        .collect();
+    pb.finish();
+
+    // Eigenvalues of the twists
+    let data_path: String = format!("{}/twist.csv", data_dir);
+    let mut writter: csv::Writer<std::fs::File> = init_file_writter(&data_path, false);
+    writter.write_record(TWIST_HEADER).unwrap();
+    for (&(theta_1, theta_2), pairs) in points.iter().zip(spectra.iter()) {
+        for (sector, pair) in sectors.iter().zip(pairs.iter()) {
+            let (dimension, info) = (sector.dimension(), pair.info);
+            write_sweep_eigenvalues(
+                &mut writter,
+                theta_1,
+                theta_2,
+                sector,
+                dimension,
+                info,
+                &pair.eigen_vals,
+            )
+            .unwrap();
+        }
+    }
+
+    // Twist averages
+    let data_path: String = format!("{}/twist_average.csv", data_dir);
+    write_averages(sectors, &spectra, &data_path);
+}
+
+/// Writes the lowest eigenvalues of every momentum block k = 2 pi m / n_sites
+/// of each sector inside 'data_dir/momentum.csv' (k in units of pi) and prints
+/// the overall ground state along with its momentum. The blocks are built and
+/// diagonalized in parallel and written in order.
+fn run_momentum(model: &Hubbard, sectors: &[Sector], solver: &SolverArgs, data_dir: &str) {
+    let n_sites: u32 = model.lattice.n_sites;
+    let blocks: Vec<(Sector, u32)> = sectors
+        .iter()
+        .flat_map(|&sector| (0..n_sites).map(move |m| (sector, m)))
+        .collect();
+
+    // Lowest eigenvalues of each momentum block (complex blocks)
+    let pb = init_progress_bar(String::from("Momenta"), blocks.len() as u64);
+    let spectra: Vec<(usize, i32, Vec<f64>)> = blocks
+        .par_iter()
+        .map(|&(sector, m)| {
+            let basis: MomentumBasis = MomentumBasis::new(sector, m);
+            let (info, mut eigen_vals) = model.get_momentum_eigenvalues(&basis);
+            eigen_vals.truncate(solver.n_eigen);
+            pb.inc(1);
+            (basis.dimension(), info, eigen_vals)
+        })
+        .collect();
+    pb.finish();
+
+    // Eigenvalues labelled by their momentum
+    let data_path: String = format!("{}/momentum.csv", data_dir);
+    let mut writter: csv::Writer<std::fs::File> = init_file_writter(&data_path, false);
+    writter.write_record(MOMENTUM_HEADER).unwrap();
+    let mut ground_state: Option<(f64, Sector, u32)> = None;
+    for (&(sector, m), (dimension, info, eigen_vals)) in blocks.iter().zip(spectra.iter()) {
+        let k: f64 = 2. * m as f64 / n_sites as f64;
+        write_sweep_eigenvalues(
+            &mut writter,
+            m as f64,
+            k,
+            &sector,
+            *dimension,
+            *info,
+            eigen_vals,
+        )
+        .unwrap();
+        if let Some(&energy) = eigen_vals.first() {
+            if ground_state.is_none_or(|(best, _, _)| energy < best) {
+                ground_state = Some((energy, sector, m));
+            }
+        }
+    }
+    if let Some((energy, sector, m)) = ground_state {
+        println!(
+            "Ground state energy: {} (n_up = {}, n_down = {}, k = {} pi)",
+            energy,
+            sector.n_up,
+            sector.n_down,
+            2. * m as f64 / n_sites as f64
+        );
+    }
+}
+
+/// Writes the lowest eigenvalues of every irreducible representation of the
+/// point group inside each sector inside 'data_dir/symmetry.csv' and prints the
+/// ground state (and its irreducible representation) of each number of
+/// fermions, from which the pairing symmetry of the two holes ground states
+/// follows. The blocks are built and diagonalized in parallel and written in
+/// order.
+fn run_symmetry(
+    model: &Hubbard,
+    sectors: &[Sector],
+    solver: &SolverArgs,
+    symmetry: &Symmetry,
+    data_dir: &str,
+) {
+    let blocks: Vec<(Sector, usize)> = sectors
+        .iter()
+        .flat_map(|&sector| (0..symmetry.irreps.len()).map(move |irrep| (sector, irrep)))
+        .collect();
+
+    // Lowest eigenvalues of each irreducible representation (representations
+    // without states in the sector are skipped)
+    let pb = init_progress_bar(String::from("Irreps"), blocks.len() as u64);
+    let (labels, spectra): (Vec<(Sector, &str, usize)>, Vec<_>) = blocks
+        .par_iter()
+        .filter_map(|&(sector, irrep)| {
+            let basis: SymmetryBasis = SymmetryBasis::new(symmetry, sector, irrep);
+            pb.inc(1);
+            if basis.dimension() == 0 {
+                return None;
+            }
+            let (info, mut eigen_vals) = model.get_symmetry_eigenvalues(&basis);
+            eigen_vals.truncate(solver.n_eigen);
+            Some((
+                (
+                    sector,
+                    symmetry.irreps[irrep].name,
+                    symmetry.irreps[irrep].dimension,
+                ),
+                (basis.dimension(), info, eigen_vals),
+            ))
+        })
+        .unzip();
+    pb.finish();
+
+    // Eigenvalues labelled by their irreducible representation
+    write_labelled_spectra(&labels, &spectra, &format!("{}/symmetry.csv", data_dir));
+}
+
+/// Writes the full spectrum of each sector along with the total spin and/or
+/// the pseudospin of each level inside 'data_dir/eigen_values.csv' (and the
+/// requested eigenvectors inside 'data_dir/eigen_vectors.csv'). With the total
+/// spin, the multiplets of the selected sectors are written inside
+/// 'data_dir/multiplets.csv' and the ground state multiplet and the spin gap
+/// of each number of fermions are printed.
+fn run_labelled_spectrum(
+    model: &Hubbard,
+    sectors: &[Sector],
+    vectors: Eigenvectors,
+    (total_spin, pseudospin): (bool, bool),
+    data_dir: &str,
+) {
+    // Tolerance on the energies of degenerate levels
+    let tolerance: f64 = 1e-8;
+
+    // Casimir operators labelling the levels (total spin first)
+    let n_sites: u32 = model.lattice.n_sites;
+    let signs: Vec<i32> = model.lattice.sublattice_signs().unwrap_or_default();
+    let spin = |state: State| spin_squared_term(state, n_sites);
+    let eta = |state: State| eta_squared_term(state, n_sites, &signs);
+    let mut header: Vec<&str> = EIGENVALUES_HEADER.to_vec();
+    if total_spin {
+        header.push("spin");
+    }
+    if pseudospin {
+        header.push("eta");
+    }
+
+    // All the eigenvectors of each sector are needed by the operators
+    let pb = init_progress_bar(String::from("Total spin"), sectors.len() as u64);
+    let spectra: Vec<(BlockEigenpairs, Vec<Vec<f64>>)> = sectors
+        .par_iter()
+        .map(|sector| {
+            let pairs: BlockEigenpairs =
+                model.get_sector_eigenpairs(sector.n_up, sector.n_down, Eigenvectors::All);
+            let mut operators: Vec<&dyn Fn(State) -> Vec<(State, f64)>> = Vec::new();
+            if total_spin {
+                operators.push(&spin);
+            }
+            if pseudospin {
+                operators.push(&eta);
+            }
+            let labels: Vec<Vec<f64>> = casimir_labels(&pairs, &operators, tolerance);
+            pb.inc(1);
+            (pairs, labels)
+        })
+        .collect();
+    pb.finish();
+
+    // Labelled levels (and eigenvectors if requested)
+    let data_path: String = format!("{}/eigen_values.csv", data_dir);
+    let mut writter: csv::Writer<std::fs::File> = init_file_writter(&data_path, false);
+    writter.write_record(&header).unwrap();
+    let mut vec_wtr: Option<csv::Writer<std::fs::File>> = match vectors {
+        Eigenvectors::Skip => None,
+        _ => {
+            let vectors_path: String = format!("{}/eigen_vectors.csv", data_dir);
+            let mut wtr = init_file_writter(&vectors_path, false);
+            wtr.write_record(EIGENVECTORS_HEADER).unwrap();
+            Some(wtr)
+        }
+    };
+    let mut levels: Vec<(Sector, f64, f64)> = Vec::new();
+    for (pairs, labels) in spectra.iter() {
+        let dimension: usize = pairs.states.len();
+        write_labelled_eigenvalues(
+            &mut writter,
+            &pairs.sector,
+            dimension,
+            pairs.info,
+            &pairs.eigen_vals,
+            labels,
+        )
+        .unwrap();
+        if let Some(wtr) = vec_wtr.as_mut() {
+            let n_vectors: usize = match vectors {
+                Eigenvectors::Lowest(k) => k.min(dimension),
+                _ => dimension,
+            };
+            write_sector_eigenvectors(
+                wtr,
+                &pairs.sector,
+                &pairs.states,
+                &pairs.eigen_vects[..n_vectors],
+            )
+            .unwrap();
+        }
+        for (energy, level_labels) in pairs.eigen_vals.iter().zip(labels) {
+            levels.push((pairs.sector, *energy, level_labels[0]));
+        }
+    }
+    if !total_spin {
+        return;
+    }
+
+    // Multiplets of the selected sectors
+    let multiplets: Vec<Multiplet> = collapse_multiplets(&levels, tolerance);
+    let data_path: String = format!("{}/multiplets.csv", data_dir);
+    let mut writter: csv::Writer<std::fs::File> = init_file_writter(&data_path, false);
+    writter.write_record(MULTIPLET_HEADER).unwrap();
+    for m in multiplets.iter() {
+        write_multiplet(
+            &mut writter,
+            m.n_particles,
+            m.energy,
+            m.spin,
+            m.multiplicity,
+        )
+        .unwrap();
+    }
+    for (ground_state, gap) in spin_gaps(&multiplets) {
+        println!(
+            "Ground state energy: {} (n_particles = {}, S = {}, multiplicity = {})",
+            ground_state.energy,
+            ground_state.n_particles,
+            ground_state.spin,
+            ground_state.multiplicity
+        );
+        if let Some(gap) = gap {
+            println!(
+                "Spin gap: {} (S = {} -> S = {})",
+                gap,
+                ground_state.spin,
+                ground_state.spin + 1.
+            );
+        }
+    }
+}
+
+/// Writes the eigenvalues of the blocks of given labels (irreducible
+/// representations or spin inversion parities, with the degeneracy of their
+/// levels) inside 'data_path' and prints the ground state of each number of
+/// fermions along with its label.
+fn write_labelled_spectra(
+    labels: &[(Sector, &str, usize)],
+    spectra: &[(usize, i32, Vec<f64>)],
+    data_path: &String,
+) {
+    let mut writter: csv::Writer<std::fs::File> = init_file_writter(data_path, false);
+    writter.write_record(SYMMETRY_HEADER).unwrap();
+    let mut ground_states: Vec<(f64, Sector, &str)> = Vec::new();
+    for (&(sector, name, degeneracy), (dimension, info, eigen_vals)) in
+        labels.iter().zip(spectra.iter())
+    {
+        write_symmetry_eigenvalues(
+            &mut writter,
+            (name, degeneracy),
+            &sector,
+            *dimension,
+            *info,
+            eigen_vals,
+        )
+        .unwrap();
+
+        // Lowest level of each number of fermions
+        let n_particles: u32 = sector.n_up + sector.n_down;
+        if let Some(&energy) = eigen_vals.first() {
+            match ground_states
+                .iter_mut()
+                .find(|(_, s, _)| s.n_up + s.n_down == n_particles)
+            {
+                Some(ground_state) if energy < ground_state.0 => {
+                    *ground_state = (energy, sector, name)
+                }
+                Some(_) => {}
+                None => ground_states.push((energy, sector, name)),
+            }
+        }
+    }
+    for (energy, sector, name) in ground_states {
+        println!(
+            "Ground state energy: {} (n_up = {}, n_down = {}, {})",
+            energy, sector.n_up, sector.n_down, name
+        );
+    }
+}
+
+/// Writes the lowest eigenvalues of the even and odd blocks of the S_z = 0
+/// sectors under the spin inversion inside 'data_dir/spin_inversion.csv' and
+/// prints the ground state of each number of fermions with its parity (even
+/// for S = 0, 2, ... and odd for S = 1, 3, ...).
+fn run_spin_inversion(model: &Hubbard, sectors: &[Sector], solver: &SolverArgs, data_dir: &str) {
+    let blocks: Vec<(Sector, &str)> = sectors
+        .iter()
+        .filter(|sector| sector.n_up == sector.n_down)
+        .flat_map(|&sector| [(sector, "even"), (sector, "odd")])
+        .collect();
+
+    // Lowest eigenvalues of each parity (the odd blocks of the empty and fully
+    // occupied sectors have no states)
+    let pb = init_progress_bar(String::from("Parities"), blocks.len() as u64);
+    let (labels, spectra): (Vec<(Sector, &str, usize)>, Vec<_>) = blocks
+        .par_iter()
+        .filter_map(|&(sector, name)| {
+            let parity: i32 = if name == "even" { 1 } else { -1 };
+            let basis: SpinInversionBasis = SpinInversionBasis::new(sector, parity);
+            pb.inc(1);
+            if basis.dimension() == 0 {
+                return None;
+            }
+            let (info, mut eigen_vals) = model.get_spin_inversion_eigenvalues(&basis);
+            eigen_vals.truncate(solver.n_eigen);
+            Some(((sector, name, 1), (basis.dimension(), info, eigen_vals)))
+        })
+        .unzip();
+    pb.finish();
+    write_labelled_spectra(
+        &labels,
+        &spectra,
+        &format!("{}/spin_inversion.csv", data_dir),
+    );
+}
+
+/// Checks that the spin inversion sectors are available: at least one S_z = 0
+/// sector, no magnetic fields (they break the symmetry), real hoppings and the
+/// LAPACK solver.
+fn check_spin_inversion_support(
+    model: &Hubbard,
+    sectors: &[Sector],
+    solver: &SolverArgs,
+) -> Result<(), String> {
+    if !sectors.iter().any(|sector| sector.n_up == sector.n_down) {
+        return Err(String::from(
+            "Spin inversion sectors need a selected sector with n_up = n_down",
+        ));
+    }
+    if model.h != 0. || model.lattice.site_fields.iter().any(|&h_i| h_i != 0.) {
+        return Err(String::from(
+            "Magnetic fields break the spin inversion symmetry",
+        ));
+    }
+    if !model.lattice.is_real() {
+        return Err(String::from("Spin inversion sectors need real hoppings"));
+    }
+    if solver.solver == Solver::Lanczos {
+        return Err(String::from(
+            "Spin inversion sectors need the LAPACK solver",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks that the drivers choosing the ground state among the selected
+/// sectors (grand-canonical and magnetization) have at least one sector to
+/// choose from.
+fn check_selection(selected: &[Sector], command: &Command) -> Result<(), String> {
+    let chooses_sector: bool = matches!(
+        command,
+        Command::GrandCanonical { .. } | Command::Magnetization { .. }
+    );
+    if chooses_sector && selected.is_empty() {
+        return Err(String::from("No sector matches the sector filters"));
+    }
+    Ok(())
+}
+
+/// Checks that the momentum sectors are available: the cluster must be a
+/// translation invariant ring and the blocks are complex hermitian (LAPACK
+/// eigenvalues).
+fn check_momentum_support(lattice: &Lattice, solver: &SolverArgs) -> Result<(), String> {
+    if !lattice.is_translation_invariant() {
+        return Err(String::from(
+            "Momentum sectors need a translation invariant ring (periodic boundaries)",
+        ));
+    }
+    if solver.solver == Solver::Lanczos {
+        return Err(String::from(
+            "Momentum sectors need the LAPACK solver (complex hermitian blocks)",
+        ));
+    }
+    Ok(())
+}
+
+/// Checks the options of the spectrum subcommand: the pseudospin needs a model
+/// conserving it (see 'Hubbard::is_eta_pairing_symmetric'), and the
+/// particle-hole mapping a particle-hole symmetric model (see
+/// 'Hubbard::is_particle_hole_symmetric') and only gives the eigenvalues.
+fn check_spectrum_options(model: &Hubbard, command: &Command) -> Result<(), String> {
+    if let Command::Spectrum {
+        vectors,
+        total_spin,
+        pseudospin,
+        particle_hole_mapping,
+        ..
+    } = command
+    {
+        if *pseudospin && !model.is_eta_pairing_symmetric() {
+            return Err(String::from(
+                "The pseudospin needs real hoppings on a bipartite cluster, with uniform energies \
+                 and interactions and without extended repulsion",
+            ));
+        }
+        if *particle_hole_mapping {
+            if *vectors != Eigenvectors::Skip || *total_spin || *pseudospin {
+                return Err(String::from(
+                    "Particle-hole mapping only gives the eigenvalues",
+                ));
+            }
+            if !model.is_particle_hole_symmetric() {
+                return Err(String::from(
+                    "Particle-hole mapping needs real hoppings on a bipartite cluster, without \
+                     magnetic fields and with uniform energies (and interactions)",
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Checks that the subcommand only uses the solvers available for complex
+/// hermitian blocks (LAPACK eigenvalues), used by twisted boundaries.
+fn check_complex_support(command: &Command) -> Result<(), String> {
+    let solver: Option<&SolverArgs> = match command {
+        Command::Spectrum {
+            vectors,
+            total_spin,
+            pseudospin,
+            ..
+        } if *vectors != Eigenvectors::Skip || *total_spin || *pseudospin => {
+            return Err(String::from("Twisted boundaries only give the eigenvalues"))
+        }
+        Command::Sweep { .. } => {
+            return Err(String::from("Twisted boundaries cannot be swept"));
+        }
+        Command::GroundState { solver, .. }
+        | Command::GrandCanonical { solver, .. }
+        | Command::Magnetization { solver, .. }
+        | Command::Disorder { solver, .. }
+        | Command::Twist { solver, .. } => Some(solver),
+        _ => None,
+    };
+    match solver {
+        Some(solver) if solver.solver == Solver::Lanczos => Err(String::from(
+            "Twisted boundaries need the LAPACK solver (complex hermitian blocks)",
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Writes the ground state (and its magnetization) for every magnetic field
+/// inside 'data_dir/magnetization.csv'. The lowest eigenvalue of each sector is
+/// computed once (at zero uniform field) and shifted by -h * (N_up - N_down).
+fn run_magnetization(
+    model: &mut Hubbard,
+    sectors: &[Sector],
+    solver: &SolverArgs,
+    field_values: &[f64],
+    data_dir: &str,
+) {
+    let data_path: String = format!("{}/magnetization.csv", data_dir);
+    let mut writter: csv::Writer<std::fs::File> = init_file_writter(&data_path, false);
+    writter.write_record(MAGNETIZATION_HEADER).unwrap();
+
+    // Lowest eigenvalue of each sector
+    model.h = 0.;
+    let lowest: Vec<(Sector, f64)> = sectors
+        .iter()
+        .map(|sector| {
+            (
+                *sector,
+                lowest_eigenvalues(model, sector, solver).eigen_vals[0],
+            )
+        })
+        .collect();
+
+    // Main loop over the magnetic fields
+    for &h in field_values.iter() {
+        if let Some((sector, energy)) = magnetized_ground_state(&lowest, h) {
+            write_magnetization_point(&mut writter, h, &sector, energy).unwrap();
+        }
+    }
+}
+
 fn main() {
     let now = Instant::now();
-    let hubbard_model = Hubbard {
-        n_sites: 6,
-        t: 1.,
-        u: 2.,
+    // Subcommand given by the flags or by a configuration file
+    let command: Command = match Cli::parse().command {
+        Command::Run { config } => read_config(&config).unwrap_or_else(|err| {
+            let msg: String = format!("Configuration file '{}': {}", config, err);
+            Cli::command().error(clap::error::ErrorKind::Io, msg).exit()
+        }),
+        command => command,
     };
-    hubbard_model.get_eigenvalues();
+    let (model, sectors) = match &command {
+        Command::Spectrum { model, sectors, .. } => (model, sectors),
+        Command::GroundState { model, sectors, .. } => (model, sectors),
+        Command::Sweep { model, sectors, .. } => (model, sectors),
+        Command::GrandCanonical { model, sectors, .. } => (model, sectors),
+        Command::Magnetization { model, sectors, .. } => (model, sectors),
+        Command::Disorder { model, sectors, .. } => (model, sectors),
+        Command::Twist { model, sectors, .. } => (model, sectors),
+        Command::Momentum { model, sectors, .. } => (model, sectors),
+        Command::Symmetry { model, sectors, .. } => (model, sectors),
+        Command::SpinInversion { model, sectors, .. } => (model, sectors),
+        Command::Run { .. } => unreachable!(),
+    };
+    let lattice = model.build_lattice().unwrap_or_else(|msg| {
+        Cli::command()
+            .error(clap::error::ErrorKind::InvalidValue, msg)
+            .exit()
+    });
+    let mut hubbard_model: Hubbard = Hubbard::new(lattice, model.t, model.u);
+    hubbard_model.v = model.v;
+    hubbard_model.mu = model.mu;
+    hubbard_model.h = model.field;
+    hubbard_model.particle_hole = model.particle_hole;
+    let selected: Vec<Sector> = sectors.select(hubbard_model.lattice.n_sites);
+    check_selection(&selected, &command).unwrap_or_else(|msg| {
+        Cli::command()
+            .error(clap::error::ErrorKind::InvalidValue, msg)
+            .exit()
+    });
+    if !hubbard_model.lattice.is_real() || matches!(command, Command::Twist { .. }) {
+        check_complex_support(&command).unwrap_or_else(|msg| {
+            Cli::command()
+                .error(clap::error::ErrorKind::ArgumentConflict, msg)
+                .exit()
+        });
+    }
+    check_spectrum_options(&hubbard_model, &command).unwrap_or_else(|msg| {
+        Cli::command()
+            .error(clap::error::ErrorKind::ArgumentConflict, msg)
+            .exit()
+    });
+    if let Command::Momentum { solver, .. } = &command {
+        check_momentum_support(&hubbard_model.lattice, solver).unwrap_or_else(|msg| {
+            Cli::command()
+                .error(clap::error::ErrorKind::ArgumentConflict, msg)
+                .exit()
+        });
+    }
+    if let Command::SpinInversion { solver, .. } = &command {
+        check_spin_inversion_support(&hubbard_model, &selected, solver).unwrap_or_else(|msg| {
+            Cli::command()
+                .error(clap::error::ErrorKind::ArgumentConflict, msg)
+                .exit()
+        });
+    }
+    let symmetry: Option<Symmetry> = match &command {
+        Command::Symmetry {
+            solver,
+            point_group,
+            ..
+        } => {
+            let symmetry: Result<Symmetry, String> = match solver.solver {
+                Solver::Lapack => Symmetry::new(*point_group, &hubbard_model.lattice),
+                Solver::Lanczos => Err(String::from("Point group sectors need the LAPACK solver")),
+            };
+            Some(symmetry.unwrap_or_else(|msg| {
+                Cli::command()
+                    .error(clap::error::ErrorKind::ArgumentConflict, msg)
+                    .exit()
+            }))
+        }
+        _ => None,
+    };
+
+    // Echoing the configuration of the run next to its data files
+    std::fs::create_dir_all(&model.output).unwrap();
+    write_config(&command, &format!("{}/run.toml", model.output)).unwrap();
+
+    // Thread pool used to diagonalize the blocks (0 uses all cores)
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(model.threads)
+        .build()
+        .unwrap();
+    pool.install(|| match &command {
+        Command::Spectrum {
+            vectors,
+            total_spin,
+            pseudospin,
+            ..
+        } if *total_spin || *pseudospin => run_labelled_spectrum(
+            &hubbard_model,
+            &selected,
+            *vectors,
+            (*total_spin, *pseudospin),
+            &model.output,
+        ),
+        Command::Spectrum {
+            vectors,
+            particle_hole_mapping,
+            ..
+        } => hubbard_model.get_eigenvalues(
+            &selected,
+            model.threads,
+            *vectors,
+            *particle_hole_mapping,
+            &model.output,
+        ),
+        Command::GroundState { solver, .. } => {
+            run_ground_state(&hubbard_model, &selected, solver, &model.output)
+        }
+        Command::Sweep {
+            solver,
+            t_values,
+            u_values,
+            ..
+        } => {
+            // Every (t, u) point of the lists (t defaults to the model value)
+            let t_values: &[f64] = if t_values.is_empty() {
+                std::slice::from_ref(&model.t)
+            } else {
+                t_values
+            };
+            let points: Vec<(f64, f64)> = t_values
+                .iter()
+                .flat_map(|&t| u_values.iter().map(move |&u| (t, u)))
+                .collect();
+            run_sweep(&hubbard_model, &selected, solver, &points, &model.output)
+        }
+        Command::GrandCanonical {
+            solver, mu_values, ..
+        } => run_grand_canonical(
+            &mut hubbard_model,
+            &selected,
+            solver,
+            mu_values,
+            &model.output,
+        ),
+        Command::Magnetization {
+            solver,
+            field_values,
+            ..
+        } => run_magnetization(
+            &mut hubbard_model,
+            &selected,
+            solver,
+            field_values,
+            &model.output,
+        ),
+        Command::Disorder {
+            solver, disorder, ..
+        } => run_disorder(&hubbard_model, &selected, solver, disorder, &model.output),
+        Command::Twist {
+            solver,
+            twist_values,
+            ..
+        } => run_twist(
+            &hubbard_model,
+            &selected,
+            solver,
+            twist_values,
+            &model.output,
+        ),
+        Command::Momentum { solver, .. } => {
+            run_momentum(&hubbard_model, &selected, solver, &model.output)
+        }
+        Command::Symmetry { solver, .. } => run_symmetry(
+            &hubbard_model,
+            &selected,
+            solver,
+            symmetry.as_ref().unwrap(),
+            &model.output,
+        ),
+        Command::SpinInversion { solver, .. } => {
+            run_spin_inversion(&hubbard_model, &selected, solver, &model.output)
+        }
+        Command::Run { .. } => unreachable!(),
+    });
     println!("Time elapsed: {:.2?}", now.elapsed());
 }
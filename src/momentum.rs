@@ -0,0 +1,222 @@
+// This module defines the momentum sectors of the Hubbard hamiltonian on the
+// ring.
+//
+// On a translation invariant ring, the translation T (site j -> site j + 1
+// modulo n_sites) commutes with the hamiltonian, so that each (N_up, N_down)
+// sector splits into n_sites blocks labelled by the momentum
+//
+//                  k = 2 pi m / n_sites    (m = 0, 1, ..., n_sites - 1)
+//
+// Translating a Fock state moves the fermion of the last site (if any) to the
+// first site, past the other N_s - 1 fermions of the same spin. Since the spin
+// up orbitals are all found before the spin down ones, the translation gives
+// the fermionic sign (-1)^(N_s - 1) for each spin whose last site is occupied
+//
+//      T | 0 1 1 ; 1 0 0 > = (-1)^1 | 1 0 1 ; 0 1 0 > = - | 1 0 1 ; 0 1 0 >
+//
+// The states of a sector are grouped into the orbits of the translation, each
+// orbit being represented by its smallest Fock state r, of period p (the
+// smallest p such that T^p | r > = s_p | r >, with s_p the fermionic sign).
+// The Bloch state of momentum k built from r
+//
+//      | r, k > = 1 / sqrt(p) sum_{j=0}^{p-1} exp(i k j) T^j | r >
+//
+// only exists if exp(i k p) s_p = 1. Writing the states linked to r by the
+// hoppings as s = c_s T^(d_s) | r' >, the matrix elements of the block are
+//
+//      < r', k | H | r, k > = sum_s h_s c_s exp(-i k d_s) sqrt(p_r / p_r')
+//
+// where h_s = < s | H | r >. The blocks are complex hermitian (real for
+// k = 0 and k = pi) and about n_sites times smaller than their sector.
+
+use std::f64::consts::PI;
+
+use crate::basis::State;
+use crate::sectors::Sector;
+
+/// Translates one spin half of a Fock state by one site along the ring. The
+/// fermion of the last site moves to the first site, past the 'n_ones - 1'
+/// other fermions of the same spin.
+fn translate_half(half: State, n_sites: u32) -> (State, i32) {
+    if half & 1 == 0 {
+        return (half >> 1, 1);
+    }
+    let sign: i32 = if half.count_ones().is_multiple_of(2) {
+        -1
+    } else {
+        1
+    };
+    ((half >> 1) | (1 << (n_sites - 1)), sign)
+}
+
+/// Translates a Fock state by one site along the ring (site j -> site j + 1),
+/// outputting the translated state along with its fermionic sign.
+///
+/// Examples
+///
+/// ```rust
+/// // | 0 1 1 ; 1 0 0 > -> - | 1 0 1 ; 0 1 0 >
+/// assert_eq!((42, -1), translate(28, 3));
+/// ```
+pub fn translate(state: State, n_sites: u32) -> (State, i32) {
+    let (up, sign_up) = translate_half(state >> n_sites, n_sites);
+    let (down, sign_down) = translate_half(state & ((1 << n_sites) - 1), n_sites);
+    ((up << n_sites) | down, sign_up * sign_down)
+}
+
+/// Finds the representative (smallest state) of the translation orbit of given
+/// Fock state, along with the number of translations 'd' and the sign 'c' such
+/// that state = c T^d representative.
+///
+/// Examples
+///
+/// ```rust
+/// // 42 = - T 28
+/// assert_eq!((28, 1, -1), representative(42, 3));
+/// ```
+pub fn representative(state: State, n_sites: u32) -> (State, u32, i32) {
+    let (mut best, mut steps, mut best_sign): (State, u32, i32) = (state, 0, 1);
+    let (mut current, mut sign): (State, i32) = (state, 1);
+
+    // Visiting the orbit: T^j state = sign * current
+    for j in 1..n_sites {
+        let (translated, translation_sign) = translate(current, n_sites);
+        current = translated;
+        sign *= translation_sign;
+        if current < best {
+            (best, steps, best_sign) = (current, j, sign);
+        }
+    }
+    (best, (n_sites - steps) % n_sites, best_sign)
+}
+
+/// Computes the period p of the translation orbit of given Fock state along
+/// with the sign s_p such that T^p state = s_p state.
+pub fn period(state: State, n_sites: u32) -> (u32, i32) {
+    let (mut current, mut sign): (State, i32) = translate(state, n_sites);
+    let mut p: u32 = 1;
+    while current != state {
+        let (translated, translation_sign) = translate(current, n_sites);
+        current = translated;
+        sign *= translation_sign;
+        p += 1;
+    }
+    (p, sign)
+}
+
+#[derive(Debug, Clone)]
+pub struct MomentumBasis {
+    // Public attributes
+    pub sector: Sector,
+    pub momentum: u32,
+    pub representatives: Vec<State>,
+    pub periods: Vec<u32>,
+}
+
+impl MomentumBasis {
+    /// Builds the basis of Bloch states of momentum k = 2 pi m / n_sites
+    /// ('momentum' = m) inside given sector, from the representatives of the
+    /// translation orbits (in increasing order).
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// // Half filling with S_z = 0 and k = pi on 6 sites
+    /// let basis: MomentumBasis = MomentumBasis::new(Sector::new(6, 3, 3), 3);
+    /// ```
+    pub fn new(sector: Sector, momentum: u32) -> MomentumBasis {
+        let n: u32 = sector.n_sites;
+        let mut representatives: Vec<State> = Vec::new();
+        let mut periods: Vec<u32> = Vec::new();
+        for state in sector.basis() {
+            let (orbit_rep, _, _) = representative(state, n);
+            if orbit_rep != state {
+                continue;
+            }
+
+            // Bloch state exists if exp(i k p) s_p = 1 (2 m p / n even or odd)
+            let (p, sign) = period(state, n);
+            let phase: u32 = (2 * momentum * p) % (2 * n);
+            if (sign > 0 && phase == 0) || (sign < 0 && phase == n) {
+                representatives.push(state);
+                periods.push(p);
+            }
+        }
+        MomentumBasis {
+            sector,
+            momentum,
+            representatives,
+            periods,
+        }
+    }
+
+    /// Momentum k = 2 pi m / n_sites of the Bloch states.
+    pub fn k(&self) -> f64 {
+        2. * PI * self.momentum as f64 / self.sector.n_sites as f64
+    }
+
+    /// Number of Bloch states inside the basis.
+    pub fn dimension(&self) -> usize {
+        self.representatives.len()
+    }
+
+    /// Outputs the index of the Bloch state built from given representative
+    /// (None if it has no Bloch state of this momentum).
+    pub fn index_of(&self, representative: State) -> Option<usize> {
+        self.representatives.binary_search(&representative).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use crate::basis::State;
+    use crate::momentum::{period, representative, translate, MomentumBasis};
+    use crate::sectors::Sector;
+
+    #[test]
+    fn test_translate() {
+        // | 0 1 1 ; 1 0 0 > -> - | 1 0 1 ; 0 1 0 >
+        assert_eq!((42, -1), translate(28, 3));
+        // | 0 1 1 ; 1 0 1 > -> | 1 0 1 ; 1 1 0 > (both spins wrap around)
+        assert_eq!((46, 1), translate(29, 3));
+        // | 1 0 0 ; 0 0 1 > -> | 0 1 0 ; 1 0 0 > (single fermions)
+        assert_eq!((20, 1), translate(33, 3));
+
+        // Back to the initial state after n_sites translations
+        let (mut state, mut sign): (State, i32) = (28, 1);
+        for _ in 0..3 {
+            let (translated, translation_sign) = translate(state, 3);
+            (state, sign) = (translated, sign * translation_sign);
+        }
+        assert_eq!((28, 1), (state, sign));
+    }
+
+    #[test]
+    fn test_translation_orbits() {
+        // | 1 0 1 0 ; 1 0 1 0 > has period 2 (each spin wraps around once)
+        assert_eq!((2, 1), period(170, 4));
+        assert_eq!((4, 1), period(195, 4));
+        // | 1 1 ; 0 0 > = - T | 1 1 ; 0 0 > only has a Bloch state at k = pi
+        assert_eq!((1, -1), period(12, 2));
+        assert_eq!(0, MomentumBasis::new(Sector::new(2, 2, 0), 0).dimension());
+        assert_eq!(1, MomentumBasis::new(Sector::new(2, 2, 0), 1).dimension());
+
+        // 42 = - T 28
+        assert_eq!((28, 1, -1), representative(42, 3));
+    }
+
+    #[test]
+    fn test_momentum_bases() {
+        // The momentum bases split the sector
+        for (n_sites, n_up, n_down) in [(6, 3, 3), (6, 2, 1), (4, 2, 2), (5, 0, 0)] {
+            let sector: Sector = Sector::new(n_sites, n_up, n_down);
+            let total: usize = (0..n_sites)
+                .map(|m| MomentumBasis::new(sector, m).dimension())
+                .sum();
+            assert_eq!(sector.dimension(), total);
+        }
+    }
+}
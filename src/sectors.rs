@@ -0,0 +1,379 @@
+// This module defines the (N_up, N_down) sectors of the Hubbard hamiltonian.
+//
+// Since hoppings conserve the number of fermions of each spin, the Fock space
+// splits into blocks labelled by the number of spin up (N_up) and spin down
+// (N_down) fermions. Instead of discovering these blocks by applying the
+// kinetic term over and over, the basis of a sector is enumerated directly
+// using the combinatorial number system: the spin up and spin down halves of
+// a Fock state are both ranked among all bit patterns having the same number
+// of ones. For example, with 3 sites, the sector (N_up, N_down) = (1, 1)
+// contains the states
+//
+//                      | 0 0 1 ; 0 0 1 > = | 9 >  (index 0)
+//                      | 0 0 1 ; 0 1 0 > = | 10 > (index 1)
+//                                ...
+//                      | 1 0 0 ; 1 0 0 > = | 36 > (index 8)
+//
+// where the index of a state inside the sector is given by
+// rank(up) * C(n_sites, N_down) + rank(down).
+
+use crate::basis::State;
+
+/// Greatest common divisor of two integers.
+fn gcd(a: usize, b: usize) -> usize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Computes the binomial coefficient C(n, k) (number of ways to choose 'k'
+/// elements among 'n'). Panics if it does not fit inside an usize (sector too
+/// large to index its states).
+///
+/// Examples
+///
+/// ```rust
+/// let n_states: usize = binomial(6, 3);
+/// println!("There are {} ways to put 3 fermions on 6 sites", n_states);
+/// ```
+pub fn binomial(n: u32, k: u32) -> usize {
+    if k > n {
+        return 0;
+    }
+    // Using symmetry C(n, k) = C(n, n - k) to minimize the number of products
+    let k: u32 = k.min(n - k);
+    let mut coefficient: usize = 1;
+    for i in 0..k {
+        // C(n, i + 1) = C(n, i) * (n - i) / (i + 1), dividing by the common
+        // factors first so that only the coefficient itself can overflow
+        let divisor: usize = (i + 1) as usize;
+        let common: usize = gcd(coefficient, divisor);
+        coefficient = (coefficient / common)
+            .checked_mul((n - i) as usize / (divisor / common))
+            .expect("Binomial coefficient too large to index the states");
+    }
+    coefficient
+}
+
+/// Ranks a bit pattern among all the bit patterns having the same number of
+/// ones, in increasing integer order (colexicographic rank).
+///
+/// Examples
+///
+/// ```rust
+/// // Patterns with two ones: 3 (0011), 5 (0101), 6 (0110), 9 (1001), ...
+/// assert_eq!(2, rank_combination(6));
+/// ```
+pub fn rank_combination(bits: State) -> usize {
+    let mut rank: usize = 0;
+    let mut remaining: State = bits;
+    let mut n_ones: u32 = 0;
+
+    // Loop over the set bits from the least significant one
+    while remaining != 0 {
+        let position: u32 = remaining.trailing_zeros();
+        n_ones += 1;
+        rank += binomial(position, n_ones);
+        remaining &= remaining - 1;
+    }
+    rank
+}
+
+/// Builds the bit pattern of given rank among all the 'n_bits' long bit
+/// patterns containing 'n_ones' ones. This is the inverse of 'rank_combination'.
+///
+/// Examples
+///
+/// ```rust
+/// assert_eq!(6, unrank_combination(2, 4, 2));
+/// ```
+pub fn unrank_combination(rank: usize, n_bits: u32, n_ones: u32) -> State {
+    let mut bits: State = 0;
+    let mut remaining: usize = rank;
+    let mut position: u32 = n_bits;
+
+    // Placing the ones from the most significant one
+    for k in (1..n_ones + 1).rev() {
+        position -= 1;
+        while binomial(position, k) > remaining {
+            position -= 1;
+        }
+        bits |= 1 << position;
+        remaining -= binomial(position, k);
+    }
+    bits
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Sector {
+    // Public attributes
+    pub n_sites: u32,
+    pub n_up: u32,
+    pub n_down: u32,
+}
+
+impl Sector {
+    /// Defines the sector containing 'n_up' spin up and 'n_down' spin down
+    /// fermions on a cluster of 'n_sites' sites.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// // Half filling with S_z = 0 on 6 sites
+    /// let sector: Sector = Sector::new(6, 3, 3);
+    /// ```
+    pub fn new(n_sites: u32, n_up: u32, n_down: u32) -> Sector {
+        assert!(
+            n_up <= n_sites && n_down <= n_sites,
+            "Sector ({}, {}) does not fit on {} sites",
+            n_up,
+            n_down,
+            n_sites
+        );
+        Sector {
+            n_sites,
+            n_up,
+            n_down,
+        }
+    }
+
+    /// Lists every sector of the Fock space of a cluster of 'n_sites' sites.
+    pub fn all(n_sites: u32) -> Vec<Sector> {
+        let mut sectors: Vec<Sector> = Vec::new();
+        for n_up in 0..n_sites + 1 {
+            for n_down in 0..n_sites + 1 {
+                sectors.push(Sector::new(n_sites, n_up, n_down));
+            }
+        }
+        sectors
+    }
+
+    /// Finds the sector containing given Fock state.
+    pub fn of_state(n_sites: u32, state: State) -> Sector {
+        let n_up: u32 = (state >> n_sites).count_ones();
+        let n_down: u32 = (state & ((1 << n_sites) - 1)).count_ones();
+        Sector::new(n_sites, n_up, n_down)
+    }
+
+    /// Finds the particle-hole image (n_sites - N_up, n_sites - N_down) of the
+    /// sector, holding the empty orbitals of its states.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// assert_eq!(Sector::new(6, 2, 1), Sector::new(6, 4, 5).particle_hole_image());
+    /// ```
+    pub fn particle_hole_image(&self) -> Sector {
+        Sector::new(
+            self.n_sites,
+            self.n_sites - self.n_up,
+            self.n_sites - self.n_down,
+        )
+    }
+
+    /// Number of Fock states inside the sector. Panics if the sector is too
+    /// large to index its states.
+    pub fn dimension(&self) -> usize {
+        binomial(self.n_sites, self.n_up)
+            .checked_mul(binomial(self.n_sites, self.n_down))
+            .expect("Sector too large to index its states")
+    }
+
+    /// Outputs the Fock state at given index of the sector basis.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let sector: Sector = Sector::new(3, 1, 1);
+    /// assert_eq!(10, sector.state(1));
+    /// ```
+    pub fn state(&self, index: usize) -> State {
+        let dim_down: usize = binomial(self.n_sites, self.n_down);
+        let up: State = unrank_combination(index / dim_down, self.n_sites, self.n_up);
+        let down: State = unrank_combination(index % dim_down, self.n_sites, self.n_down);
+        (up << self.n_sites) | down
+    }
+
+    /// Outputs the index of given Fock state inside the sector basis.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let sector: Sector = Sector::new(3, 1, 1);
+    /// assert_eq!(1, sector.index(10));
+    /// ```
+    pub fn index(&self, state: State) -> usize {
+        let dim_down: usize = binomial(self.n_sites, self.n_down);
+        let up: State = state >> self.n_sites;
+        let down: State = state & ((1 << self.n_sites) - 1);
+        rank_combination(up) * dim_down + rank_combination(down)
+    }
+
+    /// Enumerates all the Fock states of the sector in increasing order.
+    pub fn basis(&self) -> Vec<State> {
+        (0..self.dimension()).map(|idx| self.state(idx)).collect()
+    }
+}
+
+/// Finds the grand-canonical ground state at chemical potential 'mu' from the
+/// lowest energy (at zero chemical potential) of each sector, that is the
+/// sector minimizing E - mu * N (None without sectors).
+///
+/// Examples
+///
+/// ```rust
+/// let lowest: Vec<(Sector, f64)> = vec![(Sector::new(2, 0, 0), 0.), (Sector::new(2, 1, 1), -1.)];
+/// let (sector, energy) = grand_canonical_ground_state(&lowest, 0.5).unwrap();
+/// ```
+pub fn grand_canonical_ground_state(lowest: &[(Sector, f64)], mu: f64) -> Option<(Sector, f64)> {
+    shifted_ground_state(lowest, |sector| -mu * (sector.n_up + sector.n_down) as f64)
+}
+
+/// Finds the ground state in a uniform magnetic field 'h' from the lowest
+/// energy (at zero field) of each sector, that is the sector minimizing
+/// E - h * (N_up - N_down) (None without sectors).
+///
+/// Examples
+///
+/// ```rust
+/// let lowest: Vec<(Sector, f64)> = vec![(Sector::new(2, 1, 1), -2.), (Sector::new(2, 2, 0), 0.)];
+/// let (sector, energy) = magnetized_ground_state(&lowest, 1.5).unwrap();
+/// ```
+pub fn magnetized_ground_state(lowest: &[(Sector, f64)], h: f64) -> Option<(Sector, f64)> {
+    shifted_ground_state(lowest, |sector| {
+        -h * (sector.n_up as f64 - sector.n_down as f64)
+    })
+}
+
+/// Finds the sector of lowest shifted energy (the first sector wins ties).
+fn shifted_ground_state<F: Fn(&Sector) -> f64>(
+    lowest: &[(Sector, f64)],
+    shift: F,
+) -> Option<(Sector, f64)> {
+    lowest
+        .iter()
+        .map(|(sector, energy)| (*sector, energy + shift(sector)))
+        .fold(
+            None,
+            |best: Option<(Sector, f64)>, (sector, energy)| match best {
+                Some((_, best_energy)) if best_energy <= energy => best,
+                _ => Some((sector, energy)),
+            },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use crate::basis::State;
+    use crate::sectors::{
+        binomial, grand_canonical_ground_state, magnetized_ground_state, rank_combination,
+        unrank_combination, Sector,
+    };
+
+    #[test]
+    fn test_binomial() {
+        assert_eq!(1, binomial(4, 0));
+        assert_eq!(6, binomial(4, 2));
+        assert_eq!(924, binomial(12, 6));
+        assert_eq!(0, binomial(2, 3));
+
+        // No overflow of the intermediate products on 64 sites
+        assert_eq!(1832624140942590534, binomial(64, 32));
+    }
+
+    #[test]
+    fn test_combination_ranking() {
+        // Patterns with two ones on four bits in increasing order
+        let patterns: Vec<State> = vec![3, 5, 6, 9, 10, 12];
+        for (rank, bits) in patterns.iter().enumerate() {
+            assert_eq!(rank, rank_combination(*bits));
+            assert_eq!(*bits, unrank_combination(rank, 4, 2));
+        }
+    }
+
+    #[test]
+    fn test_sector_basis() {
+        // Test sector has one fermion of each spin on 3 sites
+        let sector: Sector = Sector::new(3, 1, 1);
+        let states: Vec<State> = vec![9, 10, 12, 17, 18, 20, 33, 34, 36];
+        assert_eq!(9, sector.dimension());
+        assert_eq!(states, sector.basis());
+        for (idx, state) in states.iter().enumerate() {
+            assert_eq!(idx, sector.index(*state));
+        }
+        assert_eq!(sector, Sector::of_state(3, 20));
+    }
+
+    #[test]
+    fn test_sectors_cover_fock_space() {
+        let dimension: usize = Sector::all(4).iter().map(|s| s.dimension()).sum();
+        assert_eq!(256, dimension);
+    }
+
+    #[test]
+    fn test_particle_hole_image() {
+        let sector: Sector = Sector::new(6, 4, 5);
+        assert_eq!(Sector::new(6, 2, 1), sector.particle_hole_image());
+        assert_eq!(sector, sector.particle_hole_image().particle_hole_image());
+        assert_eq!(sector.dimension(), sector.particle_hole_image().dimension());
+    }
+
+    #[test]
+    fn test_grand_canonical_ground_state() {
+        // Dimer energies at zero chemical potential (U = 4, particle-hole form)
+        let lowest: Vec<(Sector, f64)> = vec![
+            (Sector::new(2, 0, 0), 2.),
+            (Sector::new(2, 1, 0), -1.),
+            (Sector::new(2, 1, 1), -2.472),
+            (Sector::new(2, 2, 1), -1.),
+            (Sector::new(2, 2, 2), 2.),
+        ];
+        assert_eq!(
+            Sector::new(2, 1, 1),
+            grand_canonical_ground_state(&lowest, 0.).unwrap().0
+        );
+        assert_eq!(
+            Sector::new(2, 0, 0),
+            grand_canonical_ground_state(&lowest, -4.).unwrap().0
+        );
+        let (sector, energy) = grand_canonical_ground_state(&lowest, 4.).unwrap();
+        assert_eq!(Sector::new(2, 2, 2), sector);
+        assert_eq!(-14., energy);
+
+        // No ground state without sectors
+        assert_eq!(None, grand_canonical_ground_state(&[], 0.));
+    }
+
+    #[test]
+    fn test_magnetized_ground_state() {
+        // Dimer energies at zero field (U = 4, two fermions)
+        let lowest: Vec<(Sector, f64)> = vec![
+            (Sector::new(2, 0, 2), 0.),
+            (Sector::new(2, 1, 1), -0.828),
+            (Sector::new(2, 2, 0), 0.),
+        ];
+        assert_eq!(
+            Sector::new(2, 1, 1),
+            magnetized_ground_state(&lowest, 0.).unwrap().0
+        );
+        assert_eq!(
+            Sector::new(2, 1, 1),
+            magnetized_ground_state(&lowest, 0.4).unwrap().0
+        );
+        let (sector, energy) = magnetized_ground_state(&lowest, 0.5).unwrap();
+        assert_eq!(Sector::new(2, 2, 0), sector);
+        assert_eq!(-1., energy);
+        assert_eq!(
+            Sector::new(2, 0, 2),
+            magnetized_ground_state(&lowest, -0.5).unwrap().0
+        );
+
+        // No ground state without sectors
+        assert_eq!(None, magnetized_ground_state(&[], 0.));
+    }
+}
@@ -0,0 +1,151 @@
+// This module defines the spin inversion sectors of the Hubbard hamiltonian.
+//
+// Swapping the spin up and spin down halves of the Fock states
+//
+//              | u ; d >  ->  | d ; u >
+//
+// maps the sector (N_up, N_down) onto (N_down, N_up). In terms of the creation
+// operators, the spin flip c_i_up^dag <-> c_i_down^dag also moves the N_down
+// spin up operators in front of the N_up spin down ones, which gives the
+// fermionic sign (-1)^(N_up N_down), while the rotation of angle pi around the
+// x axis (c_i_up^dag -> i c_i_down^dag and c_i_down^dag -> i c_i_up^dag) gives
+// the phase i^(N_up + N_down). At S_z = 0 (N_up = N_down = N), both factors are
+// (-1)^N and cancel, so that the plain swap of the halves is the rotation
+// exp(i pi S_x), whose eigenvalue on the S_z = 0 state of a multiplet of total
+// spin S is (-1)^S.
+//
+// Without uniform or local magnetic fields, the swap commutes with the
+// hamiltonian and the S_z = 0 sectors split into the even (S = 0, 2, ...) and
+// odd (S = 1, 3, ...) blocks spanned by
+//
+//      | r, +- > = (| u ; d > +- | d ; u >) / sqrt(2)    (u < d)
+//      | r, + >  = | u ; u >
+//
+// where the representative r = | u ; d > is the smallest state of the pair.
+// Writing the states linked to r by the hoppings as s = | r' > or s = | r'' >
+// (the swapped representative), the matrix elements of the blocks are
+//
+//      < r', +- | H | r, +- > = sum_s h_s z_s sqrt(size_r / size_r')
+//
+// where h_s = < s | H | r >, z_s = 1 if s = r' and +-1 otherwise, and size_r
+// is the number of states of the pair (1 or 2).
+
+use crate::basis::State;
+use crate::sectors::Sector;
+
+/// Swaps the spin up and spin down halves of a Fock state.
+///
+/// Examples
+///
+/// ```rust
+/// // | 1 0 0 ; 0 1 1 > -> | 0 1 1 ; 1 0 0 >
+/// assert_eq!(28, swap_spins(35, 3));
+/// ```
+pub fn swap_spins(state: State, n_sites: u32) -> State {
+    let down: State = state & ((1 << n_sites) - 1);
+    (down << n_sites) | (state >> n_sites)
+}
+
+#[derive(Debug, Clone)]
+pub struct SpinInversionBasis {
+    // Public attributes
+    pub sector: Sector,
+    pub parity: i32,
+    pub representatives: Vec<State>,
+}
+
+impl SpinInversionBasis {
+    /// Builds the basis of the even ('parity' = 1) or odd ('parity' = -1)
+    /// states of given S_z = 0 sector under the spin inversion, from the
+    /// representatives of the pairs of swapped states (in increasing order).
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// // Odd states (odd total spin) at half filling on 6 sites
+    /// let basis: SpinInversionBasis = SpinInversionBasis::new(Sector::new(6, 3, 3), -1);
+    /// ```
+    pub fn new(sector: Sector, parity: i32) -> SpinInversionBasis {
+        assert_eq!(
+            sector.n_up, sector.n_down,
+            "Spin inversion needs a S_z = 0 sector"
+        );
+        let representatives: Vec<State> = sector
+            .basis()
+            .into_iter()
+            .filter(|&state| {
+                let swapped: State = swap_spins(state, sector.n_sites);
+                state < swapped || (state == swapped && parity > 0)
+            })
+            .collect();
+        SpinInversionBasis {
+            sector,
+            parity,
+            representatives,
+        }
+    }
+
+    /// Number of states inside the basis.
+    pub fn dimension(&self) -> usize {
+        self.representatives.len()
+    }
+
+    /// Outputs the representative of the pair containing given Fock state
+    /// along with the sign z_s of the state inside the basis vector (None if
+    /// the pair has no state of this parity).
+    pub fn locate(&self, state: State) -> Option<(usize, i32)> {
+        let swapped: State = swap_spins(state, self.sector.n_sites);
+        let (representative, sign): (State, i32) = if state <= swapped {
+            (state, 1)
+        } else {
+            (swapped, self.parity)
+        };
+        let index: usize = self.representatives.binary_search(&representative).ok()?;
+        Some((index, sign))
+    }
+
+    /// Number of states (1 or 2) of the pair of given representative.
+    pub fn pair_size(&self, index: usize) -> u32 {
+        let representative: State = self.representatives[index];
+        if swap_spins(representative, self.sector.n_sites) == representative {
+            1
+        } else {
+            2
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use crate::sectors::Sector;
+    use crate::spin_inversion::{swap_spins, SpinInversionBasis};
+
+    #[test]
+    fn test_swap_spins() {
+        assert_eq!(28, swap_spins(35, 3));
+        assert_eq!(35, swap_spins(swap_spins(35, 3), 3));
+        // | 1 0 1 ; 1 0 1 > is its own image
+        assert_eq!(45, swap_spins(45, 3));
+    }
+
+    #[test]
+    fn test_spin_inversion_bases() {
+        // Even and odd states split the sector, the doubly occupied
+        // configurations being even
+        let sector: Sector = Sector::new(4, 2, 2);
+        let even: SpinInversionBasis = SpinInversionBasis::new(sector, 1);
+        let odd: SpinInversionBasis = SpinInversionBasis::new(sector, -1);
+        assert_eq!(21, even.dimension());
+        assert_eq!(15, odd.dimension());
+
+        // | 0 0 1 1 ; 0 1 0 1 > - | 0 1 0 1 ; 0 0 1 1 > is the first odd state
+        assert_eq!(Some((0, 1)), odd.locate(53));
+        assert_eq!(Some((0, -1)), odd.locate(83));
+        // | 0 0 1 1 ; 0 0 1 1 > has no odd state
+        assert_eq!(None, odd.locate(51));
+        assert_eq!(1, even.pair_size(0));
+    }
+}
@@ -0,0 +1,179 @@
+// This module defines the structure of the sectors of the hamiltonian used by
+// parameter sweeps.
+//
+// The basis of a sector and the states linked by the hoppings only depend on
+// the cluster, not on the values of 't' and 'u'. SectorStructure objects store
+// them once (along with the diagonal terms in units of 'u'), so that the block
+// of the hamiltonian
+//
+//              H(t, u) = t * H_t + u * H_U + v * H_V + H_e - mu * N - h * (N_up - N_down)
+//
+// can be re-assembled (or applied to a vector) for any (t, u) point without
+// applying the second quantization operators again.
+
+use rayon::prelude::*;
+
+use crate::array_utils::{lapack_eigenpairs, Eigenvectors, Scalar};
+use crate::basis::State;
+use crate::fock_space::{BlockEigenpairs, Hubbard};
+use crate::lanczos::{lanczos_eigenpairs, Lanczos};
+use crate::sectors::Sector;
+
+#[derive(Debug)]
+pub struct SectorStructure<T: Scalar = f64> {
+    // Public attributes
+    pub sector: Sector,
+    pub states: Vec<State>,
+    pub hoppings: Vec<Vec<(usize, T)>>,
+    pub interactions: Vec<T>,
+    pub repulsions: Vec<T>,
+    pub fields: Vec<T>,
+}
+
+impl<T: Scalar> SectorStructure<T> {
+    /// Builds the structure of given sector of the model: the sector basis,
+    /// the row index and amplitude (in units of 't') of the states linked to
+    /// each state and the diagonal terms.
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let model = Hubbard::new(Lattice::chain(8), 1., 2.);
+    /// let structure = SectorStructure::new(&model, Sector::new(8, 4, 4));
+    /// let pairs = structure.eigenpairs(1., 4., Eigenvectors::Skip);
+    /// ```
+    pub fn new(model: &Hubbard<T>, sector: Sector) -> SectorStructure<T> {
+        let states: Vec<State> = sector.basis();
+        let hoppings: Vec<Vec<(usize, T)>> = states
+            .par_iter()
+            .map(|&state| {
+                model
+                    .hopping_term(state)
+                    .into_iter()
+                    .map(|(linked_state, amplitude)| (sector.index(linked_state), amplitude))
+                    .collect()
+            })
+            .collect();
+        let interactions: Vec<T> = states
+            .iter()
+            .map(|&s| model.interaction_weight(s))
+            .collect();
+        let repulsions: Vec<T> = states.iter().map(|&s| model.extended_term(s)).collect();
+        let fields: Vec<T> = states.iter().map(|&s| model.field_term(s)).collect();
+        SectorStructure {
+            sector,
+            states,
+            hoppings,
+            interactions,
+            repulsions,
+            fields,
+        }
+    }
+
+    /// Number of states inside the sector.
+    pub fn dimension(&self) -> usize {
+        self.states.len()
+    }
+
+    /// Diagonal matrix element of given row for interaction 'u'.
+    fn diagonal(&self, row: usize, u: T) -> T {
+        u * self.interactions[row] + self.repulsions[row] + self.fields[row]
+    }
+
+    /// Assembles the matrix elements of the block for given (t, u) point,
+    /// sorted 'column-wise' as LAPACK would recommend.
+    pub fn packed_elements(&self, t: T, u: T) -> Vec<T> {
+        let dimension: usize = self.dimension();
+        let mut elems: Vec<T> = Vec::with_capacity(dimension * (dimension + 1) / 2);
+        for col in 0..dimension {
+            let mut column: Vec<T> = vec![T::zero(); col + 1];
+            for &(row, amplitude) in self.hoppings[col].iter() {
+                if row < col {
+                    column[row] = t * amplitude;
+                }
+            }
+            column[col] = self.diagonal(col, u);
+            elems.append(&mut column);
+        }
+        elems
+    }
+
+    /// Applies the block of the hamiltonian for given (t, u) point to a vector
+    /// expressed in the sector basis.
+    pub fn apply(&self, t: T, u: T, vector: &[T], output: &mut [T]) {
+        output.par_iter_mut().enumerate().for_each(|(row, out)| {
+            let mut coefficient: T = self.diagonal(row, u) * vector[row];
+            for &(col, amplitude) in self.hoppings[row].iter() {
+                coefficient = coefficient + t * amplitude * vector[col];
+            }
+            *out = coefficient;
+        });
+    }
+
+    /// Outputs the eigenvalues and the requested eigenvectors of the block for
+    /// given (t, u) point using LAPACK.
+    pub fn eigenpairs(&self, t: T, u: T, vectors: Eigenvectors) -> BlockEigenpairs<T> {
+        let (info, eigen_vals, eigen_vects) =
+            lapack_eigenpairs(self.packed_elements(t, u), vectors);
+        BlockEigenpairs {
+            sector: self.sector,
+            states: self.states.clone(),
+            info,
+            eigen_vals,
+            eigen_vects,
+        }
+    }
+
+    /// Outputs the lowest eigenpairs of the block for given (t, u) point using
+    /// the matrix-free Lanczos solver ('info' is 1 if it did not converge).
+    pub fn lanczos(&self, t: T, u: T, params: &Lanczos) -> BlockEigenpairs<T> {
+        let (converged, eigen_vals, eigen_vects) = lanczos_eigenpairs(
+            self.dimension(),
+            |vector: &[T], output: &mut [T]| self.apply(t, u, vector, output),
+            params,
+        );
+        BlockEigenpairs {
+            sector: self.sector,
+            states: self.states.clone(),
+            info: if converged { 0 } else { 1 },
+            eigen_vals,
+            eigen_vects,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use crate::fock_space::Hubbard;
+    use crate::lattice::Lattice;
+    use crate::sectors::Sector;
+    use crate::structure::SectorStructure;
+
+    #[test]
+    fn test_structure_reassembly() {
+        // Structure built once from a model with other parameters
+        let sector: Sector = Sector::new(4, 2, 1);
+        let mut reference: Hubbard = Hubbard::new(Lattice::square(2, 2), 1., 1.);
+        reference.v = 0.5;
+        let structure: SectorStructure = SectorStructure::new(&reference, sector);
+        for (t, u) in [(1., 4.), (0.5, 8.), (-1., 0.)] {
+            let mut model: Hubbard = Hubbard::new(Lattice::square(2, 2), t, u);
+            model.v = 0.5;
+            let (states, elems) = model.build_sector_block(&sector);
+            assert_eq!(states, structure.states);
+            assert_eq!(elems, structure.packed_elements(t, u));
+
+            // Matrix-free product matches the hamiltonian of the model
+            let vector: Vec<f64> = (0..states.len()).map(|i| (i as f64).sin()).collect();
+            let (mut expected, mut output) = (vec![0.; states.len()], vec![0.; states.len()]);
+            model.apply_hamiltonian(&sector, &vector, &mut expected);
+            structure.apply(t, u, &vector, &mut output);
+            for (e, o) in expected.iter().zip(output.iter()) {
+                assert!((e - o).abs() < 1e-12);
+            }
+        }
+    }
+}
@@ -0,0 +1,371 @@
+// This module defines the point group sectors of the Hubbard hamiltonian.
+//
+// A point group is a set of permutations of the sites g (site i -> site g(i))
+// leaving the cluster invariant, so that the unitary operators U_g defined by
+// U_g c_i^dag U_g^-1 = c_g(i)^dag commute with the hamiltonian. Acting on a
+// Fock state, U_g moves the fermions and reorders the creation operators, which
+// gives the fermionic sign (-1)^(number of inversions) for each spin
+//
+//      U_g | 1 1 0 ; ... > = c_g(0)^dag c_g(1)^dag ... | 0 >
+//
+// The available groups are the reflection of the chains (site i -> site
+// n_sites - 1 - i, for rings and open chains) with the even and odd irreducible
+// representations, and the C4v (or D4) group of the square clusters (rotations
+// by pi / 2 and mirrors around site 0) with the A1, A2, B1, B2 and E irreducible
+// representations. The s-wave and d-wave (x^2 - y^2) symmetries correspond to
+// A1 and B1.
+//
+// The states of an irreducible representation G are obtained with the
+// projector P = sum_g D_11(g) U_g, where D_11(g) is the first diagonal element
+// of the representation matrix of g (its character for the one dimensional
+// representations, the xx element of the rotation matrix for E). The projected
+// states of each orbit of the group are orthonormalized, and the block of the
+// hamiltonian is built in this basis. It is real and only contains the first
+// partner of the two dimensional representation E, so that each level of E
+// appears once (but is twice degenerate in the whole sector).
+
+use clap::ValueEnum;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::basis::State;
+use crate::lattice::Lattice;
+use crate::sectors::Sector;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PointGroup {
+    /// Reflection of the chains (even and odd parity)
+    Reflection,
+    /// Rotations and mirrors of the square clusters (A1, A2, B1, B2 and E)
+    C4v,
+}
+
+#[derive(Debug, Clone)]
+pub struct Irrep {
+    // Public attributes
+    pub name: &'static str,
+    pub dimension: usize,
+    pub weights: Vec<f64>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Symmetry {
+    // Public attributes
+    pub n_sites: u32,
+    pub permutations: Vec<Vec<u32>>,
+    pub irreps: Vec<Irrep>,
+}
+
+/// Applies a permutation of the sites to one spin half of a Fock state,
+/// outputting the permuted half along with the sign of the reordering of its
+/// creation operators.
+fn permute_half(half: State, n_sites: u32, permutation: &[u32]) -> (State, i32) {
+    let images: Vec<u32> = (0..n_sites)
+        .filter(|&site| (half >> (n_sites - 1 - site)) & 1 == 1)
+        .map(|site| permutation[site as usize])
+        .collect();
+    let mut inversions: usize = 0;
+    for (a, image_a) in images.iter().enumerate() {
+        inversions += images[a + 1..].iter().filter(|&b| b < image_a).count();
+    }
+    let permuted: State = images
+        .iter()
+        .fold(0, |bits, &site| bits | (1 << (n_sites - 1 - site)));
+    (permuted, if inversions % 2 == 1 { -1 } else { 1 })
+}
+
+/// Applies a permutation of the sites to a Fock state, outputting the permuted
+/// state along with its fermionic sign.
+///
+/// Examples
+///
+/// ```rust
+/// // Reflection of | 1 1 0 ; 0 0 0 > gives | 0 1 1 ; 0 0 0 > = - c_2^dag c_1^dag | 0 >
+/// assert_eq!((24, -1), permute(48, 3, &[2, 1, 0]));
+/// ```
+pub fn permute(state: State, n_sites: u32, permutation: &[u32]) -> (State, i32) {
+    let (up, sign_up) = permute_half(state >> n_sites, n_sites, permutation);
+    let (down, sign_down) = permute_half(state & ((1 << n_sites) - 1), n_sites, permutation);
+    ((up << n_sites) | down, sign_up * sign_down)
+}
+
+impl Symmetry {
+    /// Builds the elements and the irreducible representations of given point
+    /// group on a cluster. Fails if the cluster is not invariant under the
+    /// group (boundaries, inequivalent sites or bonds, ...).
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// let symmetry: Symmetry = Symmetry::new(PointGroup::C4v, &Lattice::square(4, 4)).unwrap();
+    /// ```
+    pub fn new(group: PointGroup, lattice: &Lattice) -> Result<Symmetry, String> {
+        let n: u32 = lattice.n_sites;
+        let (permutations, irreps): (Vec<Vec<u32>>, Vec<Irrep>) = match group {
+            PointGroup::Reflection => {
+                let permutations: Vec<Vec<u32>> =
+                    vec![(0..n).collect(), (0..n).map(|site| n - 1 - site).collect()];
+                let irreps: Vec<Irrep> = vec![
+                    Irrep {
+                        name: "even",
+                        dimension: 1,
+                        weights: vec![1., 1.],
+                    },
+                    Irrep {
+                        name: "odd",
+                        dimension: 1,
+                        weights: vec![1., -1.],
+                    },
+                ];
+                (permutations, irreps)
+            }
+            PointGroup::C4v => {
+                if lattice.periods.is_none() {
+                    return Err(String::from("C4v needs a square cluster"));
+                }
+
+                // Rotation and mirror matrices [[m_xx, m_xy], [m_yx, m_yy]]
+                let matrices: [[i32; 4]; 8] = [
+                    [1, 0, 0, 1],
+                    [0, -1, 1, 0],
+                    [-1, 0, 0, -1],
+                    [0, 1, -1, 0],
+                    [1, 0, 0, -1],
+                    [-1, 0, 0, 1],
+                    [0, 1, 1, 0],
+                    [0, -1, -1, 0],
+                ];
+                let (x_0, y_0): (i32, i32) = lattice.coordinates[0];
+                let permutations: Vec<Vec<u32>> = matrices
+                    .iter()
+                    .map(|m| {
+                        lattice
+                            .coordinates
+                            .iter()
+                            .map(|&(x, y)| {
+                                let (dx, dy): (i32, i32) = (x - x_0, y - y_0);
+                                lattice.site_at((
+                                    x_0 + m[0] * dx + m[1] * dy,
+                                    y_0 + m[2] * dx + m[3] * dy,
+                                ))
+                            })
+                            .collect()
+                    })
+                    .collect();
+
+                // Characters (and xx element of the rotation matrix for E)
+                let character = |f: &dyn Fn(&[i32; 4]) -> i32| -> Vec<f64> {
+                    matrices.iter().map(|m| f(m) as f64).collect()
+                };
+                let b_1 = |m: &[i32; 4]| m[0] * m[0] - m[1] * m[1];
+                let det = |m: &[i32; 4]| m[0] * m[3] - m[1] * m[2];
+                let irreps: Vec<Irrep> = vec![
+                    Irrep {
+                        name: "A1",
+                        dimension: 1,
+                        weights: character(&|_| 1),
+                    },
+                    Irrep {
+                        name: "A2",
+                        dimension: 1,
+                        weights: character(&det),
+                    },
+                    Irrep {
+                        name: "B1",
+                        dimension: 1,
+                        weights: character(&b_1),
+                    },
+                    Irrep {
+                        name: "B2",
+                        dimension: 1,
+                        weights: character(&|m| det(m) * b_1(m)),
+                    },
+                    Irrep {
+                        name: "E",
+                        dimension: 2,
+                        weights: character(&|m| m[0]),
+                    },
+                ];
+                (permutations, irreps)
+            }
+        };
+
+        // Every element must be a symmetry of the cluster
+        for permutation in permutations.iter() {
+            let mut images: Vec<u32> = permutation.clone();
+            images.sort();
+            images.dedup();
+            if images.len() != n as usize || !lattice.is_invariant(permutation) {
+                return Err(format!(
+                    "Cluster is not invariant under the {:?} group",
+                    group
+                ));
+            }
+        }
+        Ok(Symmetry {
+            n_sites: n,
+            permutations,
+            irreps,
+        })
+    }
+
+    /// Outputs the images U_g | state > of a Fock state under every element of
+    /// the group, along with their fermionic signs.
+    pub fn images(&self, state: State) -> Vec<(State, i32)> {
+        self.permutations
+            .iter()
+            .map(|permutation| permute(state, self.n_sites, permutation))
+            .collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct SymmetryBasis {
+    // Public attributes
+    pub vectors: Vec<Vec<(State, f64)>>,
+
+    // Private attributes
+    overlaps: HashMap<State, Vec<(usize, f64)>>,
+}
+
+impl SymmetryBasis {
+    /// Builds the orthonormal basis of the states of given sector transforming
+    /// as the irreducible representation of index 'irrep' of the group, from
+    /// the projections of the states of each orbit (smallest states first).
+    ///
+    /// Examples
+    ///
+    /// ```rust
+    /// // d-wave (B1) states of the 2x2 plaquette at half filling
+    /// let symmetry = Symmetry::new(PointGroup::C4v, &Lattice::square(2, 2)).unwrap();
+    /// let basis: SymmetryBasis = SymmetryBasis::new(&symmetry, Sector::new(4, 2, 2), 2);
+    /// ```
+    pub fn new(symmetry: &Symmetry, sector: Sector, irrep: usize) -> SymmetryBasis {
+        let weights: &[f64] = &symmetry.irreps[irrep].weights;
+        let mut vectors: Vec<Vec<(State, f64)>> = Vec::new();
+        let mut overlaps: HashMap<State, Vec<(usize, f64)>> = HashMap::new();
+        for state in sector.basis() {
+            // Visiting each orbit once, from its smallest state
+            let images: Vec<(State, i32)> = symmetry.images(state);
+            if images.iter().any(|&(image, _)| image < state) {
+                continue;
+            }
+            let mut members: Vec<State> = images.iter().map(|&(image, _)| image).collect();
+            members.sort();
+            members.dedup();
+
+            // Projected states P | member > orthonormalized inside the orbit
+            let first: usize = vectors.len();
+            for &member in members.iter() {
+                let mut vector: Vec<f64> = vec![0.; members.len()];
+                for ((image, sign), weight) in symmetry.images(member).into_iter().zip(weights) {
+                    let idx: usize = members.binary_search(&image).unwrap();
+                    vector[idx] += weight * sign as f64;
+                }
+                for previous in vectors[first..].iter() {
+                    let overlap: f64 = previous
+                        .iter()
+                        .zip(vector.iter())
+                        .map(|((_, a), b)| a * b)
+                        .sum();
+                    for ((_, a), b) in previous.iter().zip(vector.iter_mut()) {
+                        *b -= overlap * a;
+                    }
+                }
+                let norm: f64 = vector.iter().map(|x| x * x).sum::<f64>().sqrt();
+                if norm > 1e-8 {
+                    vectors.push(
+                        members
+                            .iter()
+                            .zip(vector.iter())
+                            .map(|(&s, &x)| (s, x / norm))
+                            .collect(),
+                    );
+                }
+            }
+
+            // Components of the new basis vectors on the states of the orbit
+            for (idx, vector) in vectors.iter().enumerate().skip(first) {
+                for &(s, amplitude) in vector.iter() {
+                    if amplitude.abs() > 1e-12 {
+                        overlaps.entry(s).or_default().push((idx, amplitude));
+                    }
+                }
+            }
+        }
+        SymmetryBasis { vectors, overlaps }
+    }
+
+    /// Number of states inside the basis.
+    pub fn dimension(&self) -> usize {
+        self.vectors.len()
+    }
+
+    /// Outputs the (index, amplitude) pairs of the basis vectors having a
+    /// component on given Fock state.
+    pub fn overlaps(&self, state: State) -> &[(usize, f64)] {
+        self.overlaps
+            .get(&state)
+            .map_or(&[], |pairs| pairs.as_slice())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use crate::lattice::{Boundary, Lattice};
+    use crate::momentum::translate;
+    use crate::sectors::Sector;
+    use crate::symmetry::{permute, PointGroup, Symmetry, SymmetryBasis};
+
+    #[test]
+    fn test_permute() {
+        // Reflection of | 1 1 0 ; 0 0 0 > gives - | 0 1 1 ; 0 0 0 >
+        assert_eq!((24, -1), permute(48, 3, &[2, 1, 0]));
+        assert_eq!((10, 1), permute(34, 3, &[2, 1, 0]));
+
+        // Same signs as the translations of the ring
+        for state in [28, 29, 33, 46] {
+            assert_eq!(translate(state, 3), permute(state, 3, &[1, 2, 0]));
+        }
+    }
+
+    #[test]
+    fn test_symmetry_groups() {
+        let ring: Lattice = Lattice::chain(6);
+        assert_eq!(
+            2,
+            Symmetry::new(PointGroup::Reflection, &ring)
+                .unwrap()
+                .permutations
+                .len()
+        );
+        assert!(Symmetry::new(PointGroup::C4v, &ring).is_err());
+        let mut open: Lattice = Lattice::chain(6);
+        open.boundaries = [Boundary::Open; 2];
+        assert!(Symmetry::new(PointGroup::Reflection, &open).is_ok());
+
+        // Tilted cluster of 10 sites has no mirror
+        assert!(Symmetry::new(PointGroup::C4v, &Lattice::square(4, 4)).is_ok());
+        assert!(Symmetry::new(PointGroup::C4v, &Lattice::betts(10)).is_err());
+    }
+
+    #[test]
+    fn test_symmetry_bases() {
+        // Irreducible representations split the sector (E counted twice)
+        let symmetry: Symmetry = Symmetry::new(PointGroup::C4v, &Lattice::square(3, 3)).unwrap();
+        for (n_up, n_down) in [(1, 1), (2, 1), (3, 3)] {
+            let sector: Sector = Sector::new(9, n_up, n_down);
+            let total: usize = (0..symmetry.irreps.len())
+                .map(|irrep| {
+                    symmetry.irreps[irrep].dimension
+                        * SymmetryBasis::new(&symmetry, sector, irrep).dimension()
+                })
+                .sum();
+            assert_eq!(sector.dimension(), total);
+        }
+    }
+}
@@ -0,0 +1,334 @@
+// This module defines the total spin labels of the eigenstates of the Hubbard
+// hamiltonian.
+//
+// Without magnetic fields (or with a uniform one), the hamiltonian commutes
+// with the total spin S^2 = S^- S^+ + S_z^2 + S_z, so that each eigenvalue of
+// a (N_up, N_down) sector belongs to a multiplet of total spin S, whose 2S + 1
+// states are found in the sectors of N = N_up + N_down fermions with
+// |N_up - N_down| / 2 <= S. The total spin of each level is read from the
+// expectation value
+//
+//                      < psi | S^2 | psi > = S (S + 1)
+//
+// of its eigenvector. Degenerate levels of a sector (|E_a - E_b| < tolerance)
+// can however mix multiplets of different total spins, so that S^2 is
+// diagonalized inside the degenerate subspace spanned by their eigenvectors
+// instead (the projected S^2 matrix < psi_a | S^2 | psi_b >).
+//
+// On bipartite clusters with uniform interactions, the hamiltonian also
+// commutes with the total pseudospin eta^2 built from the eta-pairing
+// operator eta^+ = sum_i epsilon_i c_i_up^dag c_i_down^dag (epsilon_i = +-1 on
+// the two sublattices) and eta_z = (N - n_sites) / 2, whose multiplets relate
+// the sectors of N and N + 2 fermions (the half filled states of largest eta
+// being the eta-pairing states). Both labels are found at once: S^2 is
+// diagonalized inside each degenerate subspace, then eta^2 inside each of its
+// eigenspaces.
+//
+// The labelled levels of all the selected sectors are finally collapsed into
+// multiplets: the levels of same number of fermions, same energy and same
+// total spin are counted once along with their multiplicity, which is 2S + 1
+// (times the orbital degeneracy) when every S_z sector of N is selected.
+
+use crate::array_utils::{lapack_eigenpairs, Eigenvectors};
+use crate::basis::State;
+use crate::fock_space::BlockEigenpairs;
+use crate::sectors::Sector;
+
+/// Computes the total spin S from the eigenvalue S (S + 1) of S^2, rounded to
+/// the nearest half integer (kept as is if too far from a half integer, for
+/// fields breaking the SU(2) symmetry).
+///
+/// Examples
+///
+/// ```rust
+/// assert_eq!(1., total_spin(2.));
+/// ```
+pub fn total_spin(s_squared: f64) -> f64 {
+    let spin: f64 = ((1. + 4. * s_squared).max(0.).sqrt() - 1.) / 2.;
+    // Absolute value avoids the negative zero of the singlets
+    let half_integer: f64 = (2. * spin).round().abs() / 2.;
+    if (spin - half_integer).abs() < 1e-6 {
+        half_integer
+    } else {
+        spin
+    }
+}
+
+/// Computes the matrix of an operator (given by its action 'term' on the Fock
+/// states) inside the subspace spanned by given orthonormal vectors of a
+/// sector, as the upper triangle stored 'column-wise'.
+fn projected_elements<F>(
+    sector: &Sector,
+    states: &[State],
+    vectors: &[Vec<f64>],
+    term: F,
+) -> Vec<f64>
+where
+    F: Fn(State) -> Vec<(State, f64)>,
+{
+    let mut elems: Vec<f64> = Vec::with_capacity(vectors.len() * (vectors.len() + 1) / 2);
+    for (col, vector) in vectors.iter().enumerate() {
+        // Operator applied to the vector in the sector basis
+        let mut image: Vec<f64> = vec![0.; sector.dimension()];
+        for (state, amplitude) in states.iter().zip(vector.iter()) {
+            for (linked_state, element) in term(*state) {
+                image[sector.index(linked_state)] += element * amplitude;
+            }
+        }
+        for row_vector in vectors[..=col].iter() {
+            let overlap: f64 = states
+                .iter()
+                .zip(row_vector.iter())
+                .map(|(state, amplitude)| amplitude * image[sector.index(*state)])
+                .sum();
+            elems.push(overlap);
+        }
+    }
+    elems
+}
+
+/// Labels the vectors of a degenerate subspace by the total spins of the
+/// operators: the first operator is diagonalized inside the subspace, then
+/// the next ones inside each of its eigenspaces (one label per operator).
+fn refine_labels(
+    sector: &Sector,
+    states: &[State],
+    vectors: &[Vec<f64>],
+    operators: &[&dyn Fn(State) -> Vec<(State, f64)>],
+) -> Vec<Vec<f64>> {
+    let Some((operator, others)) = operators.split_first() else {
+        return vec![Vec::new(); vectors.len()];
+    };
+    let elems: Vec<f64> = projected_elements(sector, states, vectors, operator);
+    let (_, values, rotations) = lapack_eigenpairs(elems, Eigenvectors::All);
+
+    // Eigenvectors of the operator inside the subspace
+    let rotated: Vec<Vec<f64>> = rotations
+        .iter()
+        .map(|rotation| {
+            let mut vector: Vec<f64> = vec![0.; states.len()];
+            for (weight, old) in rotation.iter().zip(vectors.iter()) {
+                for (component, amplitude) in vector.iter_mut().zip(old.iter()) {
+                    *component += weight * amplitude;
+                }
+            }
+            vector
+        })
+        .collect();
+
+    // Eigenspaces [first, last) of the operator refined by the other operators
+    let mut labels: Vec<Vec<f64>> = Vec::with_capacity(vectors.len());
+    let mut first: usize = 0;
+    while first < values.len() {
+        let mut last: usize = first + 1;
+        while last < values.len() && (values[last] - values[first]).abs() < 1e-6 {
+            last += 1;
+        }
+        for mut sub_labels in refine_labels(sector, states, &rotated[first..last], others) {
+            sub_labels.insert(0, total_spin(values[first]));
+            labels.push(sub_labels);
+        }
+        first = last;
+    }
+    labels
+}
+
+/// Computes the total spins of every level of a sector from its eigenpairs
+/// (all the eigenvectors are needed) for each of the given Casimir operators
+/// (such as 'spin_squared_term'), the operators being diagonalized inside
+/// each subspace of degenerate levels (|E_a - E_b| < 'tolerance'). Outputs one
+/// label per operator for each level.
+///
+/// Examples
+///
+/// ```rust
+/// let model = Hubbard::new(Lattice::chain(4), 1., 4.);
+/// let pairs: BlockEigenpairs = model.get_sector_eigenpairs(2, 2, Eigenvectors::All);
+/// let spin = |state: State| spin_squared_term(state, 4);
+/// let labels: Vec<Vec<f64>> = casimir_labels(&pairs, &[&spin], 1e-8);
+/// ```
+pub fn casimir_labels(
+    pairs: &BlockEigenpairs,
+    operators: &[&dyn Fn(State) -> Vec<(State, f64)>],
+    tolerance: f64,
+) -> Vec<Vec<f64>> {
+    let mut labels: Vec<Vec<f64>> = Vec::with_capacity(pairs.eigen_vals.len());
+    let mut first: usize = 0;
+    while first < pairs.eigen_vals.len() {
+        // Degenerate levels [first, last)
+        let mut last: usize = first + 1;
+        while last < pairs.eigen_vals.len()
+            && (pairs.eigen_vals[last] - pairs.eigen_vals[first]).abs() < tolerance
+        {
+            last += 1;
+        }
+        labels.append(&mut refine_labels(
+            &pairs.sector,
+            &pairs.states,
+            &pairs.eigen_vects[first..last],
+            operators,
+        ));
+        first = last;
+    }
+    labels
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Multiplet {
+    // Public attributes
+    pub n_particles: u32,
+    pub energy: f64,
+    pub spin: f64,
+    pub multiplicity: usize,
+}
+
+/// Collapses the labelled levels (sector, energy, total spin) into multiplets
+/// of same number of fermions, energy (within 'tolerance') and total spin,
+/// sorted by number of fermions, energy and total spin.
+///
+/// Examples
+///
+/// ```rust
+/// // Triplet found in the three S_z sectors of two fermions
+/// let levels: Vec<(Sector, f64, f64)> = vec![
+///     (Sector::new(2, 2, 0), 0., 1.),
+///     (Sector::new(2, 1, 1), 0., 1.),
+///     (Sector::new(2, 0, 2), 0., 1.),
+/// ];
+/// assert_eq!(3, collapse_multiplets(&levels, 1e-8)[0].multiplicity);
+/// ```
+pub fn collapse_multiplets(levels: &[(Sector, f64, f64)], tolerance: f64) -> Vec<Multiplet> {
+    let mut sorted: Vec<(u32, f64, f64)> = levels
+        .iter()
+        .map(|(sector, energy, spin)| (sector.n_up + sector.n_down, *energy, *spin))
+        .collect();
+    sorted.sort_by(|a, b| a.0.cmp(&b.0).then(a.1.partial_cmp(&b.1).unwrap()));
+
+    // Degenerate levels of same number of fermions, then grouped by total spin
+    let mut multiplets: Vec<Multiplet> = Vec::new();
+    let mut first: usize = 0;
+    while first < sorted.len() {
+        let (n_particles, energy, _) = sorted[first];
+        let mut last: usize = first + 1;
+        while last < sorted.len()
+            && sorted[last].0 == n_particles
+            && (sorted[last].1 - energy).abs() < tolerance
+        {
+            last += 1;
+        }
+        let mut group: Vec<Multiplet> = Vec::new();
+        for &(_, _, spin) in sorted[first..last].iter() {
+            match group.iter_mut().find(|m| (m.spin - spin).abs() < 1e-6) {
+                Some(multiplet) => multiplet.multiplicity += 1,
+                None => group.push(Multiplet {
+                    n_particles,
+                    energy,
+                    spin,
+                    multiplicity: 1,
+                }),
+            }
+        }
+        group.sort_by(|a, b| a.spin.partial_cmp(&b.spin).unwrap());
+        multiplets.append(&mut group);
+        first = last;
+    }
+    multiplets
+}
+
+/// Computes the spin gap of each number of fermions, the energy difference
+/// between the lowest multiplet of total spin S_0 + 1 and the ground state
+/// multiplet of total spin S_0 (None if no such multiplet was found).
+pub fn spin_gaps(multiplets: &[Multiplet]) -> Vec<(Multiplet, Option<f64>)> {
+    let mut gaps: Vec<(Multiplet, Option<f64>)> = Vec::new();
+    for multiplet in multiplets.iter() {
+        if gaps
+            .iter()
+            .any(|(m, _)| m.n_particles == multiplet.n_particles)
+        {
+            continue;
+        }
+        let excited: Option<f64> = multiplets
+            .iter()
+            .find(|m| {
+                m.n_particles == multiplet.n_particles
+                    && (m.spin - multiplet.spin - 1.).abs() < 1e-6
+            })
+            .map(|m| m.energy - multiplet.energy);
+        gaps.push((multiplet.clone(), excited));
+    }
+    gaps
+}
+
+#[cfg(test)]
+mod tests {
+
+    use std::assert_eq;
+
+    use crate::array_utils::Eigenvectors;
+    use crate::basis::State;
+    use crate::fock_space::{eta_squared_term, spin_squared_term, BlockEigenpairs, Hubbard};
+    use crate::lattice::Lattice;
+    use crate::sectors::Sector;
+    use crate::total_spin::{casimir_labels, collapse_multiplets, spin_gaps, total_spin};
+
+    #[test]
+    fn test_total_spin() {
+        assert_eq!(0., total_spin(0.));
+        assert!(total_spin(-1e-12).is_sign_positive());
+        assert_eq!(0.5, total_spin(0.75));
+        assert_eq!(1., total_spin(2. + 1e-10));
+        assert_eq!(1.5, total_spin(3.75));
+    }
+
+    #[test]
+    fn test_casimir_labels() {
+        // Two sites: singlet ground state, triplet at 0, eta-pairing state at
+        // U and singlet
+        let model: Hubbard = Hubbard::new(Lattice::dimer(), 1., 4.);
+        let pairs: BlockEigenpairs = model.get_sector_eigenpairs(1, 1, Eigenvectors::All);
+        let spin = |state: State| spin_squared_term(state, 2);
+        let eta = |state: State| eta_squared_term(state, 2, &[1, -1]);
+        assert_eq!(
+            vec![vec![0., 0.], vec![1., 0.], vec![0., 1.], vec![0., 0.]],
+            casimir_labels(&pairs, &[&spin, &eta], 1e-8)
+        );
+
+        // Free fermions: degenerate levels of different total spins
+        let model: Hubbard = Hubbard::new(Lattice::chain(4), 1., 0.);
+        let pairs: BlockEigenpairs = model.get_sector_eigenpairs(2, 1, Eigenvectors::All);
+        let spin = |state: State| spin_squared_term(state, 4);
+        let spins: Vec<f64> = casimir_labels(&pairs, &[&spin], 1e-8)
+            .iter()
+            .map(|labels| labels[0])
+            .collect();
+        assert!(spins.iter().all(|&s| s == 0.5 || s == 1.5));
+        // Quartets: (2, 1) holds one S_z state of each of the C(4, 3) quartets
+        assert_eq!(4, spins.iter().filter(|&&s| s == 1.5).count());
+    }
+
+    #[test]
+    fn test_multiplets() {
+        // Levels of all the sectors of 2 fermions on 2 sites
+        let model: Hubbard = Hubbard::new(Lattice::dimer(), 1., 4.);
+        let mut levels: Vec<(Sector, f64, f64)> = Vec::new();
+        for (n_up, n_down) in [(2, 0), (1, 1), (0, 2)] {
+            let pairs: BlockEigenpairs =
+                model.get_sector_eigenpairs(n_up, n_down, Eigenvectors::All);
+            let spin = |state: State| spin_squared_term(state, 2);
+            let labels: Vec<Vec<f64>> = casimir_labels(&pairs, &[&spin], 1e-8);
+            for (energy, label) in pairs.eigen_vals.iter().zip(labels) {
+                levels.push((pairs.sector, *energy, label[0]));
+            }
+        }
+        let multiplets = collapse_multiplets(&levels, 1e-8);
+        assert_eq!(4, multiplets.len());
+        assert_eq!((0., 1), (multiplets[0].spin, multiplets[0].multiplicity));
+        assert_eq!((1., 3), (multiplets[1].spin, multiplets[1].multiplicity));
+        assert!(multiplets[1].energy.abs() < 1e-10);
+
+        // Singlet-triplet splitting 4 t^2 / U at large U (exactly J = (sqrt(U^2 + 16) - U) / 2)
+        let (ground_state, gap) = spin_gaps(&multiplets)[0].clone();
+        assert_eq!(2, ground_state.n_particles);
+        assert!((gap.unwrap() - (32f64.sqrt() - 4.) / 2.).abs() < 1e-10);
+    }
+}